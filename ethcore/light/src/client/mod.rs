@@ -16,6 +16,7 @@
 
 //! Light client implementation. Stores data from light sync
 
+use std::io;
 use std::sync::{Weak, Arc};
 
 use ethcore::block_status::BlockStatus;
@@ -280,6 +281,11 @@ impl<T: ChainDataFetcher> Client<T> {
 		self.queue.flush()
 	}
 
+	/// Flush the buffered writes of the database to disk.
+	pub fn flush_database(&self) -> io::Result<()> {
+		self.db.flush()
+	}
+
 	/// Get the `i`th CHT root.
 	pub fn cht_root(&self, i: usize) -> Option<H256> {
 		self.chain.cht_root(i)
@@ -150,4 +150,9 @@ pub trait ChainNotify : Send + Sync {
 	) {
 		// does nothing by default
 	}
+
+	/// fires when the client fails to write to its database, right before it panics
+	fn database_error(&self, _error: &str) {
+		// does nothing by default
+	}
 }
@@ -16,6 +16,8 @@
 
 use std::collections::{HashSet, BTreeMap, VecDeque};
 use std::cmp;
+use std::fmt;
+use std::io;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::sync::{Arc, Weak};
@@ -30,9 +32,9 @@ use trie::{TrieSpec, TrieFactory, Trie};
 use kvdb::{DBValue, KeyValueDB, DBTransaction};
 
 // other
-use ethereum_types::{H256, Address, U256};
+use ethereum_types::{H256, Address, U256, Bloom};
 use block::{IsBlock, LockedBlock, Drain, ClosedBlock, OpenBlock, enact_verified, SealedBlock};
-use blockchain::{BlockChain, BlockChainDB, BlockProvider, TreeRoute, ImportRoute, TransactionAddress, ExtrasInsert};
+use blockchain::{BlockChain, BlockChainDB, BlockProvider, TreeRoute, ImportRoute, TransactionAddress, ExtrasInsert, BlockDetails, BlockReceipts};
 use client::ancient_import::AncientVerifier;
 use client::{
 	Nonce, Balance, ChainInfo, BlockInfo, CallContract, TransactionInfo,
@@ -48,6 +50,7 @@ use client::{
 	IoClient, BadBlocks,
 };
 use client::bad_blocks;
+use db::Writable;
 use encoded;
 use engines::{EthEngine, EpochTransition, ForkChoice};
 use error::{
@@ -71,7 +74,7 @@ use spec::Spec;
 use state_db::StateDB;
 use state::{self, State};
 use trace;
-use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase};
+use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase, FlatBlockTraces};
 use transaction::{self, LocalizedTransaction, UnverifiedTransaction, SignedTransaction, Transaction, Action};
 use types::filter::Filter;
 use types::ancestry_action::AncestryAction;
@@ -348,7 +351,7 @@ impl Importer {
 		}
 
 		let db = client.db.read();
-		db.key_value().flush().expect("DB flush failed.");
+		db.key_value().flush().unwrap_or_else(|e| client.database_failure("DB flush failed", e));
 		imported
 	}
 
@@ -533,7 +536,7 @@ impl Importer {
 			client
 		);
 
-		state.journal_under(&mut batch, number, hash).expect("DB commit failed");
+		state.journal_under(&mut batch, number, hash).unwrap_or_else(|e| client.database_failure("DB commit failed", e));
 
 		let finalized: Vec<_> = ancestry_actions.into_iter().map(|ancestry_action| {
 			let AncestryAction::MarkFinalized(a) = ancestry_action;
@@ -695,7 +698,7 @@ impl Importer {
 			// always write the batch directly since epoch transition proofs are
 			// fetched from a DB iterator and DB iterators are only available on
 			// flushed data.
-			client.db.read().key_value().write(batch).expect("DB flush failed");
+			client.db.read().key_value().write(batch).unwrap_or_else(|e| client.database_failure("DB flush failed", e));
 		}
 	}
 }
@@ -865,6 +868,14 @@ impl Client {
 		&*self.engine
 	}
 
+	// Reports a database failure to the notifiers, then panics: the client can't go on without its
+	// database.
+	fn database_failure<E: fmt::Display>(&self, context: &str, err: E) -> ! {
+		let message = format!("{}: {}", context, err);
+		self.notify(|notify| notify.database_error(&message));
+		panic!("{}", message);
+	}
+
 	fn notify<F>(&self, f: F) where F: Fn(&ChainNotify) {
 		for np in &*self.notify.read() {
 			if let Some(n) = np.upgrade() {
@@ -886,6 +897,11 @@ impl Client {
 		}
 	}
 
+	/// Flush the buffered writes of the database to disk.
+	pub fn flush_database(&self) -> io::Result<()> {
+		self.db.read().key_value().flush()
+	}
+
 	/// The env info as of the best block.
 	pub fn latest_env_info(&self) -> EnvInfo {
 		self.env_info(BlockId::Latest).expect("Best block header always stored; qed")
@@ -1172,6 +1188,64 @@ impl Client {
 		self.history
 	}
 
+	/// Reverts the canonical chain to the block with the given number, deleting the blocks after
+	/// it, their traces and their blooms so that they can be imported again. Only archive databases
+	/// still have the state of that block.
+	pub fn revert_to(&self, number: BlockNumber) -> Result<(), String> {
+		if self.pruning != journaldb::Algorithm::Archive {
+			return Err("Reverting the chain requires an archive database, from `--pruning archive`".into());
+		}
+
+		// Same locks as `restore_db`: the caches are rebuilt from the database once it's written.
+		let _import_lock = self.importer.import_lock.lock();
+		let mut state_db = self.state_db.write();
+		let mut chain = self.chain.write();
+		let mut tracedb = self.tracedb.write();
+		let best_number = chain.best_block_number();
+		if number >= best_number {
+			return Err(format!("Block #{} isn't older than the best block #{}", number, best_number));
+		}
+		let missing = |n| format!("Block #{} is missing from the database", n);
+		let hash = chain.block_hash(number).ok_or_else(|| missing(number))?;
+		let mut details = chain.block_details(&hash).ok_or_else(|| missing(number))?;
+
+		let mut batch = DBTransaction::new();
+		for n in (number + 1)..(best_number + 1) {
+			let removed = chain.block_hash(n).ok_or_else(|| missing(n))?;
+			if let Some(body) = chain.block_body(&removed) {
+				for transaction in body.transaction_hashes() {
+					Writable::delete::<TransactionAddress, _>(&mut batch, ::db::COL_EXTRA, &transaction);
+				}
+			}
+			batch.delete(::db::COL_HEADERS, &removed);
+			batch.delete(::db::COL_BODIES, &removed);
+			Writable::delete::<BlockDetails, _>(&mut batch, ::db::COL_EXTRA, &removed);
+			Writable::delete::<BlockReceipts, _>(&mut batch, ::db::COL_EXTRA, &removed);
+			Writable::delete::<H256, _>(&mut batch, ::db::COL_EXTRA, &n);
+			Writable::delete::<FlatBlockTraces, _>(&mut batch, ::db::COL_TRACE, &removed);
+			details.children.retain(|child| *child != removed);
+		}
+		batch.write(::db::COL_EXTRA, &hash, &details);
+		batch.put(::db::COL_EXTRA, b"best", &hash);
+
+		let db = self.db.read();
+		db.key_value().write(batch)
+			.map_err(|e| format!("Cannot write the reverted chain to the database: {}", e))?;
+
+		let cache_size = state_db.cache_size();
+		*state_db = StateDB::new(journaldb::new(db.key_value().clone(), self.pruning, ::db::COL_STATE), cache_size);
+		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
+		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+
+		// Blooms are stored by block number, so the removed blocks' are cleared rather than deleted.
+		let empty_blooms = vec![Bloom::default(); (best_number - number) as usize];
+		db.blooms().insert_blooms(number + 1, empty_blooms.iter())
+			.and_then(|_| db.trace_blooms().insert_blooms(number + 1, empty_blooms.iter()))
+			.map_err(|e| format!("Cannot clear the blooms of the reverted blocks: {}", e))?;
+		info!(target: "client", "Reverted the chain to #{} ({})", number, hash);
+		Ok(())
+	}
+
 	fn block_hash(chain: &BlockChain, id: BlockId) -> Option<H256> {
 		match id {
 			BlockId::Hash(hash) => Some(hash),
@@ -2312,7 +2386,7 @@ impl ImportSealedBlock for Client {
 				start.elapsed(),
 			);
 		});
-		self.db.read().key_value().flush().expect("DB flush failed.");
+		self.db.read().key_value().flush().unwrap_or_else(|e| self.database_failure("DB flush failed", e));
 		Ok(h)
 	}
 }
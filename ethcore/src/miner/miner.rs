@@ -329,6 +329,30 @@ impl Miner {
 		});
 	}
 
+	/// Sets the minimal gas price of the transactions accepted by the queue, replacing the
+	/// automatic calibration if any.
+	pub fn set_minimal_gas_price(&self, gas_price: U256, block_gas_limit: U256) {
+		*self.gas_pricer.lock() = GasPricer::new_fixed(gas_price);
+		self.update_transaction_queue_limits(block_gas_limit);
+	}
+
+	/// Seals a new block right away, even if there are no pending transactions.
+	///
+	/// Only works with engines that seal internally, such as the instant seal engine of
+	/// development chains. Returns whether a block was sealed and imported.
+	pub fn force_seal_block<C>(&self, chain: &C) -> bool where
+		C: BlockChain + CallContract + BlockProducer + SealedBlockImporter + Nonce + Sync,
+	{
+		if self.engine.seals_internally() != Some(true) {
+			return false;
+		}
+
+		match self.prepare_block(chain) {
+			Some((block, _)) => self.seal_and_import_block(chain, block),
+			None => false,
+		}
+	}
+
 	/// Retrieves an existing pending block iff it's not older than given block number.
 	///
 	/// NOTE: This will not prepare a new pending block if it's not existing.
@@ -622,6 +646,14 @@ impl Miner {
 			}
 		}
 
+		self.seal_and_import_block(chain, block)
+	}
+
+	/// Seals and imports a block even if it contains no transactions, for engines sealing
+	/// internally.
+	fn seal_and_import_block<C>(&self, chain: &C, block: ClosedBlock) -> bool
+		where C: BlockChain + SealedBlockImporter,
+	{
 		trace!(target: "miner", "seal_block_internally: attempting internal seal.");
 
 		let parent_header = match chain.block_header(BlockId::Hash(*block.header().parent_hash())) {
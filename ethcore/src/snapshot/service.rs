@@ -290,8 +290,8 @@ impl Service {
 		Ok(service)
 	}
 
-	// get the current snapshot dir.
-	fn snapshot_dir(&self) -> PathBuf {
+	/// Get the directory of the local snapshot.
+	pub fn snapshot_dir(&self) -> PathBuf {
 		let mut dir = self.snapshot_root.clone();
 		dir.push("current");
 		dir
@@ -18,7 +18,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 use hash::keccak;
 use io::IoChannel;
-use client::{BlockChainClient, Client, ClientConfig, BlockId, ChainInfo, BlockInfo, PrepareOpenBlock, ImportSealedBlock, ImportBlock};
+use client::{BlockChainClient, Client, ClientConfig, BlockId, TransactionId, ChainInfo, BlockInfo, PrepareOpenBlock, ImportSealedBlock, ImportBlock};
 use state::{self, State, CleanupMode};
 use executive::{Executive, TransactOptions};
 use ethereum;
@@ -292,6 +292,66 @@ fn change_history_size() {
 	assert_eq!(client.state().balance(&address).unwrap(), 100.into());
 }
 
+#[test]
+fn reverts_chain_to_block() {
+	let db = test_helpers::new_db();
+	let test_spec = Spec::new_null();
+	let mut config = ClientConfig::default();
+	config.pruning = ::journaldb::Algorithm::Archive;
+	let key = KeyPair::from_secret(keccak("test").into()).unwrap();
+
+	let new_client = || Client::new(
+		config.clone(),
+		&test_spec,
+		db.clone(),
+		Arc::new(Miner::new_for_tests(&test_spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+	// Imports a block with a single transaction and returns the hashes of both.
+	let push_block = |client: &Client, nonce: u64, to: Address| {
+		let transaction = Transaction {
+			nonce: nonce.into(),
+			gas_price: 0.into(),
+			gas: 21000.into(),
+			action: Action::Call(to),
+			value: 0.into(),
+			data: Vec::new(),
+		}.sign(key.secret(), None);
+		let transaction_hash = transaction.hash();
+		let mut b = client.prepare_open_block(Address::default(), (3141562.into(), 31415620.into()), vec![]).unwrap();
+		b.push_transaction(transaction, None).unwrap();
+		let b = b.close_and_lock().unwrap().seal(&*test_spec.engine, vec![]).unwrap();
+		(client.import_sealed_block(b).unwrap(), transaction_hash)
+	};
+	let transaction_location = |client: &Client, hash| client.transaction(TransactionId::Hash(hash))
+		.map(|t| (t.block_hash, t.transaction_index));
+
+	let client = new_client();
+	let blocks: Vec<_> = (0..5).map(|nonce| push_block(&client, nonce, Address::default())).collect();
+	assert_eq!(client.chain_info().best_block_number, 5);
+
+	client.revert_to(2).unwrap();
+	assert_eq!(client.chain_info().best_block_number, 2);
+	assert_eq!(client.chain_info().best_block_hash, blocks[1].0);
+	assert_eq!(client.block_hash(BlockId::Number(3)), None);
+	assert_eq!(transaction_location(&client, blocks[2].1), None);
+	assert_eq!(transaction_location(&client, blocks[1].1), Some((blocks[1].0, 0)));
+
+	let (reimported, transaction_hash) = push_block(&client, 2, Address::from(1));
+	assert_ne!(reimported, blocks[2].0);
+	drop(client);
+
+	let client = new_client();
+	assert_eq!(client.chain_info().best_block_number, 3);
+	assert_eq!(client.chain_info().best_block_hash, reimported);
+	assert_eq!(client.block_hash(BlockId::Number(3)), Some(reimported));
+	assert_eq!(client.block_hash(BlockId::Number(4)), None);
+	assert_eq!(transaction_location(&client, transaction_hash), Some((reimported, 0)));
+	for &(_, removed) in &blocks[2..] {
+		assert_eq!(transaction_location(&client, removed), None);
+	}
+}
+
 #[test]
 fn does_not_propagate_delayed_transactions() {
 	let key = KeyPair::from_secret(keccak("test").into()).unwrap();
@@ -566,6 +566,13 @@ pub trait ManageNetwork : Send + Sync {
 	/// Note that `range.end` is *exclusive*.
 	// TODO: Range should be changed to RangeInclusive once stable (https://github.com/rust-lang/rust/pull/50758)
 	fn num_peers_range(&self) -> Range<u32>;
+	/// Set the minimum and maximum peers, disconnecting the peers above the new limits.
+	fn set_peers_range(&self, min_peers: u32, max_peers: u32);
+	/// Returns the TCP port the node accepts peers on and the UDP port of the discovery, if the
+	/// network is running.
+	fn listen_ports(&self) -> Option<(u16, u16)>;
+	/// Returns the public key of the node, which its enode URL contains, if the network is running.
+	fn node_id(&self) -> Option<H512>;
 	/// Get network context for protocol.
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext));
 }
@@ -608,6 +615,18 @@ impl ManageNetwork for EthSync {
 		self.network.num_peers_range()
 	}
 
+	fn set_peers_range(&self, min_peers: u32, max_peers: u32) {
+		self.network.set_peer_limits(min_peers, max_peers);
+	}
+
+	fn listen_ports(&self) -> Option<(u16, u16)> {
+		self.network.listen_ports()
+	}
+
+	fn node_id(&self) -> Option<H512> {
+		self.network.node_id()
+	}
+
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
@@ -887,6 +906,18 @@ impl ManageNetwork for LightSync {
 		self.network.num_peers_range()
 	}
 
+	fn set_peers_range(&self, min_peers: u32, max_peers: u32) {
+		self.network.set_peer_limits(min_peers, max_peers);
+	}
+
+	fn listen_ports(&self) -> Option<(u16, u16)> {
+		self.network.listen_ports()
+	}
+
+	fn node_id(&self) -> Option<H512> {
+		self.network.node_id()
+	}
+
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut FnMut(&NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
@@ -130,17 +130,25 @@ pub fn setup_log(config: &Config) -> Result<Arc<RotatingLogger>, String> {
     };
 
 	builder.format(format);
-	builder.try_init()
-		.and_then(|_| {
+	match builder.try_init() {
+		Ok(_) => {
 			*ROTATING_LOGGER.lock() = Arc::downgrade(&logs);
 			Ok(logs)
-		})
+		},
 		// couldn't create new logger - try to fall back on previous logger.
-		.or_else(|err| match ROTATING_LOGGER.lock().upgrade() {
-			Some(l) => Ok(l),
-			// no previous logger. fatal.
-			None => Err(format!("{:?}", err)),
-		})
+		Err(_) => {
+			let previous = ROTATING_LOGGER.lock().upgrade();
+			match previous {
+				Some(l) => Ok(l),
+				// no previous logger: a logger was installed by the application embedding parity.
+				// Keep using it; `logs` won't receive any record in that case.
+				None => {
+					*ROTATING_LOGGER.lock() = Arc::downgrade(&logs);
+					Ok(logs)
+				},
+			}
+		},
+	}
 }
 
 fn kill_color(s: &str) -> String {
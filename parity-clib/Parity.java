@@ -45,6 +45,36 @@ public class Parity {
         return rpcQueryNative(inner, query);
     }
 
+    /** Subscribes to notifications through a PubSub RPC query, such as `eth_subscribe`.
+     *
+     * The listener first receives the response to the subscription query, then each
+     * notification, from a background thread.
+     *
+     * @param query The JSON-encoded subscription query to perform
+     * @param listener The object receiving the JSON-encoded messages
+     * @return An identifier to pass to `unsubscribe`
+     */
+    public long subscribe(String query, NotificationListener listener) {
+        return subscribe(inner, query, listener);
+    }
+
+    /** Cancels a subscription created with `subscribe`.
+     *
+     * Notifications that were already queued may still be delivered for a short while.
+     *
+     * @param subscription The identifier returned by `subscribe`
+     */
+    public void unsubscribe(long subscription) {
+        unsubscribe(inner, subscription);
+    }
+
+    /**
+     * Receives the messages of a subscription.
+     */
+    public interface NotificationListener {
+        void onNotification(String notification);
+    }
+
     @Override
     protected void finalize​() {
         destroy(inner);
@@ -58,6 +88,8 @@ public class Parity {
     private static native long build(long config);
     private static native void destroy(long inner);
     private static native String rpcQueryNative(long inner, String rpc);
+    private static native long subscribe(long inner, String query, NotificationListener listener);
+    private static native void unsubscribe(long inner, long subscription);
 
     private long inner;
 }
@@ -18,6 +18,21 @@
 #define _PARITY_H_INCLUDED_
 
 #include <stddef.h>
+#include <stdint.h>
+
+// # Memory ownership
+//
+// - Strings and buffers passed to Parity are only read during the call, unless documented
+//   otherwise. They don't need to be null-terminated, as their length is always passed along.
+// - Buffers filled by Parity, such as the output of `parity_rpc_sync`, are allocated and freed by
+//   the caller. This lets garbage-collected languages like Go pass their own memory.
+// - Strings passed to callbacks are owned by Parity and only valid until the callback returns.
+// - Strings returned through a `char**` parameter, such as by `parity_enode`, are owned by the
+//   caller and must be freed with `parity_string_destroy`.
+// - Strings returned through a `const char**` parameter, such as by `parity_version`, are owned
+//   by Parity and must not be freed.
+// - Configuration objects and clients are opaque `void*` handles, which must be destroyed with
+//   the corresponding `*_destroy` function, or passed to `parity_start` for configurations.
 
 /// Parameters to pass to `parity_start`.
 struct ParityParams {
@@ -37,6 +52,280 @@ struct ParityParams {
 	void *on_client_restart_cb_custom;
 };
 
+/// Peer connected to a client, as passed to the callback of `parity_peers`.
+///
+/// The strings are not null-terminated, and are only valid during the call of the callback.
+struct ParityPeerInfo {
+	/// Enode URL of the peer, or an empty string if its node id isn't known yet.
+	const char* enode;
+	size_t enode_len;
+
+	/// Client version advertised by the peer, such as `Parity-Ethereum/v2.3.0-.../rustc1.31.0`.
+	const char* client_version;
+	size_t client_version_len;
+
+	/// Version of the protocol the client syncs with the peer, `eth` for full clients and `pip`
+	/// for light clients, or 0 if the peer doesn't speak that protocol.
+	uint32_t protocol_version;
+
+	/// Hash of the best block of the peer, or 32 zero bytes if `protocol_version` is 0.
+	uint8_t head_hash[32];
+};
+
+/// Log matching the filter of a `parity_subscribe_logs` subscription, or emitted by the
+/// transaction of a `ParityReceipt`.
+///
+/// The pointers are only valid during the call of the callback.
+struct ParityLog {
+	/// Address of the contract that emitted the log.
+	uint8_t address[20];
+
+	/// Topics of the log, as `topics_count` consecutive hashes of 32 bytes.
+	const uint8_t* topics;
+	size_t topics_count;
+
+	/// Data of the log.
+	const uint8_t* data;
+	size_t data_len;
+
+	/// Number and hash of the block, and hash of the transaction, that emitted the log.
+	uint64_t block_number;
+	uint8_t block_hash[32];
+	uint8_t transaction_hash[32];
+
+	/// Position of the transaction in the block, and of the log among all the logs of the block.
+	size_t transaction_index;
+	size_t log_index;
+
+	/// 1 if the block of the log was removed from the canonical chain by a reorganization, in
+	/// which case the log should be reverted, and 0 otherwise.
+	int removed;
+};
+
+/// Block of the canonical chain, passed by `parity_get_block`.
+///
+/// The pointers are only valid during the call of the callback.
+struct ParityBlock {
+	/// Number and hash of the block, and hash of its parent.
+	uint64_t number;
+	uint8_t hash[32];
+	uint8_t parent_hash[32];
+
+	/// Timestamp of the block, in seconds since the Unix epoch.
+	uint64_t timestamp;
+
+	/// Gas used by the transactions of the block, and gas limit of the block.
+	uint64_t gas_used;
+	uint64_t gas_limit;
+
+	/// Number of transactions in the block.
+	size_t transaction_count;
+
+	/// Hashes of the transactions, as `transaction_count` consecutive hashes of 32 bytes in the
+	/// order of the block, or NULL if they weren't requested.
+	const uint8_t* transaction_hashes;
+};
+
+/// Receipt of a mined transaction, passed by `parity_get_receipt`.
+///
+/// The pointers are only valid during the call of the callback.
+struct ParityReceipt {
+	/// A `ParityReceiptStatus`.
+	int status;
+
+	/// Gas used by the transaction, and by the transactions of the block up to and including it.
+	uint64_t gas_used;
+	uint64_t cumulative_gas_used;
+
+	/// 1 if the transaction created a contract, whose address is then in `contract_address`, and
+	/// 0 otherwise.
+	int has_contract_address;
+	uint8_t contract_address[20];
+
+	/// Number and hash of the block that includes the transaction, and position of the
+	/// transaction in the block.
+	uint64_t block_number;
+	uint8_t block_hash[32];
+	size_t transaction_index;
+
+	/// Logs emitted by the transaction, in order. Their `removed` field is always 0.
+	const struct ParityLog* logs;
+	size_t logs_count;
+};
+
+/// Error codes returned by the fallible `parity_*` functions.
+///
+/// The values are guaranteed to stay the same across releases; new codes may be added at the end.
+enum ParityError {
+	/// The function succeeded.
+	PARITY_OK = 0,
+	/// A string passed to the function is not valid UTF-8.
+	PARITY_ERROR_INVALID_UTF8 = 1,
+	/// The CLI arguments could not be parsed.
+	PARITY_ERROR_CLI_PARSE = 2,
+	/// The client could not be started.
+	PARITY_ERROR_START_FAILED = 3,
+	/// A required callback was NULL.
+	PARITY_ERROR_NULL_CALLBACK = 4,
+	/// The operation did not complete in time.
+	PARITY_ERROR_TIMEOUT = 5,
+	/// The output buffer is too small to hold the result.
+	PARITY_ERROR_BUFFER_TOO_SMALL = 6,
+	/// The RPC request didn't produce a response.
+	PARITY_ERROR_RPC_FAILED = 7,
+	/// A panic happened in the Rust code. A panic always indicates a bug in Parity.
+	PARITY_ERROR_PANIC = 8,
+	/// The config file could not be read.
+	PARITY_ERROR_CONFIG_READ = 9,
+	/// The config file is not valid.
+	PARITY_ERROR_CONFIG_PARSE = 10,
+	/// A logger was already installed for the process.
+	PARITY_ERROR_LOGGER_ALREADY_SET = 11,
+	/// Not returned anymore. The value stays reserved.
+	PARITY_ERROR_CANCELLED = 12,
+	/// A configuration value is not valid.
+	PARITY_ERROR_INVALID_VALUE = 13,
+	/// The network of the client is disabled.
+	PARITY_ERROR_NETWORK_DISABLED = 14,
+	/// The database is in use by another process, usually another instance of Parity running
+	/// with the same data directory.
+	PARITY_ERROR_DATABASE_LOCKED = 15,
+	/// The HTTP JSON-RPC server of the client is disabled.
+	PARITY_ERROR_HTTP_SERVER_DISABLED = 16,
+	/// The client or the configuration object passed to the function is NULL. The functions that
+	/// don't return an error code do nothing when passed NULL instead.
+	PARITY_ERROR_NULL_POINTER = 17,
+	/// The operation isn't supported by this kind of client, for example mining parameters on a
+	/// light client.
+	PARITY_ERROR_UNSUPPORTED = 18,
+	/// The requested value isn't available, or not yet.
+	PARITY_ERROR_UNAVAILABLE = 19,
+	/// No block could be sealed.
+	PARITY_ERROR_SEAL_FAILED = 20,
+	/// The accounts of the client could not be read from the key store.
+	PARITY_ERROR_ACCOUNTS_UNAVAILABLE = 21,
+	/// The buffered changes of the database could not be written to disk.
+	PARITY_ERROR_FLUSH_FAILED = 22,
+	/// A snapshot could not be taken or restored.
+	PARITY_ERROR_SNAPSHOT_FAILED = 23,
+	/// The data directory, or the database in it, could not be created or opened because of its
+	/// permissions.
+	PARITY_ERROR_PERMISSION_DENIED = 24,
+	/// The contract call reverted.
+	PARITY_ERROR_CALL_REVERTED = 25,
+	/// The contract call failed, for example because it ran out of gas, or couldn't be executed.
+	PARITY_ERROR_CALL_FAILED = 26,
+	/// The handle passed to the function points to another type of object, for example a
+	/// configuration where a client is expected, or was already destroyed.
+	PARITY_ERROR_WRONG_HANDLE_TYPE = 27,
+	/// The password doesn't match the account.
+	PARITY_ERROR_INVALID_PASSWORD = 28,
+	/// The client doesn't manage the account.
+	PARITY_ERROR_UNKNOWN_ACCOUNT = 29,
+	/// The account must be unlocked first, for example with `parity_unlock_account`.
+	PARITY_ERROR_ACCOUNT_LOCKED = 30,
+	/// The chain could not be reverted to the requested block.
+	PARITY_ERROR_REVERT_FAILED = 31,
+};
+
+/// Bits of the status returned by `parity_node_health`.
+///
+/// The values are part of the public API and will never change.
+enum ParityNodeHealth {
+	/// The node is performing a major sync or restoring a snapshot.
+	PARITY_NODE_HEALTH_SYNCING = 1 << 0,
+	/// The node is connected to at least one peer.
+	PARITY_NODE_HEALTH_PEERS_OK = 1 << 1,
+	/// The node isn't connected to any peer.
+	PARITY_NODE_HEALTH_NO_PEERS = 1 << 2,
+	/// The number of peers doesn't matter, because the node runs a development chain.
+	PARITY_NODE_HEALTH_PEERS_CHECK_DISABLED = 1 << 3,
+};
+
+/// Outcomes of `parity_import_block`.
+///
+/// The values are part of the public API and will never change.
+enum ParityBlockImport {
+	/// The block was added to the import queue, or already was in it.
+	PARITY_BLOCK_IMPORT_QUEUED = 0,
+	/// The block is already part of the chain.
+	PARITY_BLOCK_IMPORT_ALREADY_IN_CHAIN = 1,
+	/// The block is invalid. `parity_last_error_message` tells why.
+	PARITY_BLOCK_IMPORT_REJECTED = 2,
+};
+
+/// Statuses of the receipts passed by `parity_get_receipt`.
+///
+/// The values are part of the public API and will never change.
+enum ParityReceiptStatus {
+	/// The transaction failed, for example because it reverted or ran out of gas. Its gas was
+	/// still paid for.
+	PARITY_RECEIPT_STATUS_FAILED = 0,
+	/// The transaction succeeded.
+	PARITY_RECEIPT_STATUS_SUCCEEDED = 1,
+	/// The transaction was mined before the Byzantium fork, whose receipts have no status.
+	PARITY_RECEIPT_STATUS_UNKNOWN = 2,
+};
+
+/// States of the transactions returned by `parity_transaction_status`.
+///
+/// The values are part of the public API and will never change.
+enum ParityTransactionStatus {
+	/// The transaction is neither in the chain nor in the transaction queue of the client.
+	PARITY_TRANSACTION_STATUS_UNKNOWN = 0,
+	/// The transaction is in the transaction queue, ready to be included in the next block.
+	PARITY_TRANSACTION_STATUS_PENDING = 1,
+	/// The transaction is in the transaction queue, but can't be included in a block yet, for
+	/// example because a transaction of the same sender with a lower nonce is missing.
+	PARITY_TRANSACTION_STATUS_QUEUED = 2,
+	/// The transaction is part of the canonical chain.
+	PARITY_TRANSACTION_STATUS_MINED = 3,
+	/// The transaction was sent through the client but left the transaction queue without being
+	/// mined, for example because it was replaced, had its nonce used by another transaction, or
+	/// the queue was full.
+	PARITY_TRANSACTION_STATUS_DROPPED = 4,
+};
+
+/// Modes of `parity_set_panic_mode`.
+///
+/// The values are part of the public API and will never change.
+enum ParityPanicMode {
+	/// Panics are caught by the functions, which return `PARITY_ERROR_PANIC`. This is the default.
+	PARITY_PANIC_MODE_RECOVER = 0,
+	/// Panics abort the process, with a backtrace.
+	PARITY_PANIC_MODE_ABORT = 1,
+};
+
+/// Kinds of clients returned by `parity_client_mode`.
+///
+/// The values are part of the public API and will never change.
+enum ParityClientMode {
+	/// Full client keeping the state of every block, started with `--pruning archive`. All the
+	/// state-reading functions work for any block.
+	PARITY_CLIENT_MODE_ARCHIVE = 0,
+	/// Full client only keeping the state of the recent blocks, as set with `--pruning-history`.
+	/// The state-reading functions work for the best block, but the state of older blocks, such
+	/// as the one `parity_trace_transaction` replays transactions on, may be pruned.
+	PARITY_CLIENT_MODE_FULL = 1,
+	/// Light client, started with `--light`, which keeps no state. The state-reading functions
+	/// return `PARITY_ERROR_UNSUPPORTED`.
+	PARITY_CLIENT_MODE_LIGHT = 2,
+};
+
+/// What `parity_set_subscription_buffer` does with a notification when the buffer is full.
+///
+/// The values are part of the public API and will never change.
+enum ParitySubscriptionBufferPolicy {
+	/// The notification waits for room in the buffer, which delays the following notifications
+	/// as well. This is the default.
+	PARITY_SUBSCRIPTION_BUFFER_BLOCK = 0,
+	/// The oldest notification of the buffer is dropped to make room, for feeds where only the
+	/// latest notifications matter.
+	PARITY_SUBSCRIPTION_BUFFER_DROP_OLDEST = 1,
+	/// The notification is dropped, so that the buffered notifications are delivered in full.
+	PARITY_SUBSCRIPTION_BUFFER_DROP_NEWEST = 2,
+};
+
 #ifdef __cplusplus
 extern "C" {
 #endif
@@ -48,7 +337,9 @@ extern "C" {
 ///
 /// On success, the produced object will be written to the `void*` pointed by `out`.
 ///
-/// Returns 0 on success, and non-zero on error.
+/// Returns 0 on success, and a `ParityError` code on error. When an argument is invalid, the
+/// message returned by `parity_last_error_message` names it, for example
+/// `error: Found argument '--nonsense' which wasn't expected, or isn't valid in this context`.
 ///
 /// # Example
 ///
@@ -63,8 +354,180 @@ extern "C" {
 ///
 int parity_config_from_cli(char const* const* args, size_t const* arg_lens, size_t len, void** out);
 
+/// Builds a new configuration object by loading a TOML config file.
+///
+/// The file is loaded the same way as with the `--config` CLI flag. `path` and `path_len` are the
+/// path of the file, which doesn't need to be zero-terminated.
+///
+/// On success, the produced object will be written to the `void*` pointed by `out`.
+///
+/// Returns 0 on success, and a `ParityError` code on error. `PARITY_ERROR_CONFIG_READ` means that
+/// the file couldn't be read, and `PARITY_ERROR_CONFIG_PARSE` that its contents are invalid.
+int parity_config_from_file(const char* path, size_t path_len, void** out);
+
+/// Creates a copy of a configuration object created earlier.
+///
+/// The copy is independent from the original: each of them must be passed to either
+/// `parity_start` or `parity_config_destroy` separately.
+///
+/// On success, the produced object will be written to the `void*` pointed by `out`.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_config_clone(void const* cfg, void** out);
+
+/// Sets the chain to run, as with the `--chain` command line option. `chain` can be the name of
+/// a known chain, such as `kovan`, or the path to a chain spec file. Replaces any chain spec set
+/// with `parity_config_set_chain_spec_json`.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_UTF8` if `chain` isn't valid UTF-8, and
+/// `PARITY_ERROR_INVALID_VALUE` if it is empty.
+///
+/// **Important**: `chain` doesn't need to be null-terminated.
+int parity_config_set_chain(void* cfg, const char* chain, size_t len);
+
+/// Sets the chain to run from the JSON of its chain spec, as found in a chain spec file, for
+/// example for a private chain generated at runtime. Nothing is written to the filesystem, and the
+/// spec replaces the chain set with `--chain` or `parity_config_set_chain`.
+///
+/// The JSON is only parsed by `parity_start`, which fails with `PARITY_ERROR_START_FAILED` if it
+/// isn't a valid chain spec.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_UTF8` if `json` isn't valid UTF-8, and
+/// `PARITY_ERROR_INVALID_VALUE` if it is empty.
+int parity_config_set_chain_spec_json(void* cfg, const char* json, size_t len);
+
+/// Sets the base data storage path, as with the `--base-path` command line option.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_UTF8` if `path` isn't valid UTF-8, and
+/// `PARITY_ERROR_INVALID_VALUE` if it is empty.
+///
+/// **Important**: `path` doesn't need to be null-terminated.
+int parity_config_set_datadir(void* cfg, const char* path, size_t len);
+
+/// Sets the file the logs are appended to, in addition to the other outputs, as with the
+/// `--log-file` command line option. The file is created when the client starts if it doesn't
+/// exist, and isn't rotated by Parity. This function only checks that the file or its directory
+/// can be written.
+///
+/// The logger is set up once per process, by the first client started, so the file is ignored by
+/// the clients started afterwards and if a logger was installed with `parity_set_logger`.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_UTF8` if `path` isn't valid UTF-8, and
+/// `PARITY_ERROR_INVALID_VALUE` if it is empty or neither the file nor its directory can be
+/// written.
+///
+/// **Important**: `path` doesn't need to be null-terminated.
+int parity_config_set_log_file(void* cfg, const char* path, size_t len);
+
+/// Sets the nodes the client connects to first to find peers, as with the `--bootnodes` command
+/// line option, for example to join a private network.
+///
+/// `enodes` and `lens` are the string pointers and lengths of the enode URLs, such as
+/// `enode://1a2b...@192.168.0.1:30303`, and must have a length equal to `count`. The strings don't
+/// need to be zero-terminated. The list replaces the bootnodes of the chain, and an empty list
+/// leaves the client with none.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if an entry isn't a valid enode URL or its
+/// hostname can't be resolved, and a `ParityError` code on error.
+int parity_config_set_bootnodes(void* cfg, char const* const* enodes, size_t const* lens, size_t count);
+
+/// Sets how the client determines the public address it advertises to peers, as with the `--nat`
+/// command line option, for example when running in a container or behind a router.
+///
+/// `nat` and `len` must contain one of `any`, the default, `upnp`, `none`, or `extip:` followed
+/// by the public IP address, such as `extip:1.2.3.4`. The string doesn't need to be
+/// zero-terminated.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `nat` isn't one of these, and a
+/// `ParityError` code on error.
+int parity_config_set_nat(void* cfg, const char* nat, size_t len);
+
+/// Sets the reserved peers of the client, which it stays connected to even above the peer limits,
+/// as with the `--reserved-peers` command line option but without writing them to a file.
+///
+/// `enodes` and `lens` are the string pointers and lengths of the enode URLs, and must have a
+/// length equal to `count`. The strings don't need to be zero-terminated. The list replaces the
+/// file given with `--reserved-peers`, if any. Combine with `--reserved-only` to only connect to
+/// these peers, for example on a permissioned network.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if an entry isn't a valid enode URL or its
+/// hostname can't be resolved, and a `ParityError` code on error.
+int parity_config_set_reserved_peers(void* cfg, char const* const* enodes, size_t const* lens, size_t count);
+
+/// Sets the port of the JSON-RPC HTTP server, as with the `--jsonrpc-port` command line option.
+/// If `port` is 0, the system picks a free port, which `parity_jsonrpc_http_address` then returns.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_config_set_jsonrpc_port(void* cfg, uint16_t port);
+
+/// Sets the number of peers the client tries to maintain, as with the `--min-peers` command line
+/// option.
+///
+/// Returns 0 on success, and `PARITY_ERROR_INVALID_VALUE` if `min_peers` is greater than the
+/// maximum number of peers of the configuration.
+int parity_config_set_min_peers(void* cfg, uint16_t min_peers);
+
+/// Sets the origins allowed to query the JSON-RPC HTTP server from a web page, as with the
+/// `--jsonrpc-cors` command line option, for example `http://localhost:3000`.
+///
+/// `origins` and `lens` are the string pointers and lengths of the origins, and must have a length
+/// equal to `count`. The strings don't need to be zero-terminated. A single "all" origin allows
+/// every origin, and an empty list allows none, which is the default.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if an origin is empty, contains a comma or
+/// whitespace, or if "all" or "none" is combined with other origins, and a `ParityError` code on
+/// error.
+int parity_config_set_jsonrpc_cors(void* cfg, char const* const* origins, size_t const* lens, size_t count);
+
+/// Sets the values of the `Host` header accepted by the JSON-RPC HTTP server, as with the
+/// `--jsonrpc-hosts` command line option, for example `localhost:8545`.
+///
+/// `hosts` and `lens` are the string pointers and lengths of the hosts, and must have a length
+/// equal to `count`. The strings don't need to be zero-terminated. A single "all" host accepts any
+/// `Host` header, and an empty list accepts only the address of the server, which is the default.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if a host is empty, contains a comma or
+/// whitespace, or if "all" or "none" is combined with other hosts, and a `ParityError` code on
+/// error.
+int parity_config_set_jsonrpc_hosts(void* cfg, char const* const* hosts, size_t const* lens, size_t count);
+
+/// Sets the API namespaces enabled on the JSON-RPC servers, as with the `--jsonrpc-apis` command
+/// line option, for example `eth` and `net` without `personal` or `parity_set`.
+///
+/// `apis` and `lens` are the string pointers and lengths of the names, and must have a length
+/// equal to `count`. The strings don't need to be zero-terminated. Besides the names of the APIs,
+/// "all" enables every API, "safe" the ones that are safe to expose over HTTP, and a name prefixed
+/// with "-" disables an API enabled by the entries before it, as in `all` followed by `-personal`.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `count` is 0 or an entry isn't a known
+/// API, and a `ParityError` code on error.
+int parity_config_set_jsonrpc_apis(void* cfg, char const* const* apis, size_t const* lens, size_t count);
+
+/// Sets the number of threads processing the queries of all the JSON-RPC servers, as with the
+/// `--jsonrpc-threads` command line option, instead of the default of 4.
+///
+/// Returns 0 on success, and `PARITY_ERROR_INVALID_VALUE` if `threads` is 0.
+int parity_config_set_jsonrpc_threads(void* cfg, uint32_t threads);
+
+/// Enables or disables the in-memory mode, for disposable test nodes: if `enable` isn't 0, the
+/// client keeps its blockchain database in memory, and the database is lost when the client is
+/// destroyed. Together with the instant seal of the `dev` chain, this gives a fast node to run
+/// tests against.
+///
+/// Unless the configuration has a base path, the other files of the client, such as its keys and
+/// network key, go to a temporary directory, which is removed when the client is destroyed, so
+/// that the client leaves no files behind. Restoring a snapshot isn't supported in this mode.
+///
+/// This mode is unsuitable for real chains, which don't fit in memory and would have to be synced
+/// again on every start.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_config_set_in_memory(void* cfg, int enable);
+
 /// Destroys a configuration object created earlier.
 ///
+/// Does nothing if `cfg` is NULL. Otherwise, must be called exactly once per configuration object.
+///
 /// **Important**: You probably don't need to call this function. Calling `parity_start` destroys
 /// 				the configuration object as well (even on failure).
 void parity_config_destroy(void* cfg);
@@ -75,33 +538,1059 @@ void parity_config_destroy(void* cfg);
 /// **Important**: The configuration object passed inside `cfg` is destroyed when you
 /// 				call `parity_start` (even on failure).
 ///
+/// Several clients can run in the same process, for example one on the main network and one on a
+/// test network. Each of them must use its own base path and ports, and gets its own handle. The
+/// logger and the panic hook are shared by all the clients of the process: the logging options of
+/// the clients started after the first one are ignored.
+///
 /// On success, the produced object will be written to the `void*` pointed by `out`.
 ///
-/// Returns 0 on success, and non-zero on error.
-int parity_start(const ParityParams* params, void** out);
+/// Returns 0 on success, and a `ParityError` code on error. `PARITY_ERROR_DATABASE_LOCKED` means
+/// that another process already uses the database of the chosen chain, and
+/// `PARITY_ERROR_PERMISSION_DENIED` that the data directory or the database can't be written by
+/// the process. Call `parity_last_error_message` to find out why the client failed to start.
+int parity_start(const struct ParityParams* params, void** out);
+
+/// Restarts a client on another chain, with the configuration it was started with otherwise.
+///
+/// `parity` must point to the handle of the client, and `new_chain` and `len` contain the new
+/// chain, as with `parity_config_set_chain`. The client is shut down, the `on_client_restart_cb`
+/// callback it was started with is called with the new chain, and a new client is started. Its
+/// handle is written to the `void*` pointed by `parity`; the old handle becomes invalid, and so do
+/// its subscriptions and callbacks. The RPC timeout is kept.
+///
+/// Returns 0 on success, and a `ParityError` code on error. If the new client fails to start, the
+/// `void*` pointed by `parity` is set to NULL, and `parity_last_error_message` tells why. If
+/// `new_chain` is invalid, the client isn't restarted.
+int parity_restart(void** parity, const char* new_chain, size_t len);
+
+/// Reverts the chain to the block with the given number and restarts the client, which then
+/// imports the following blocks again, for example to recover from a bad block that was accepted.
+/// This is what stopping the node and deleting the blocks from its database would do, without the
+/// separate tooling.
+///
+/// `parity` must point to the handle of the client. Only full nodes with `--pruning archive`,
+/// whose database is on disk, can be reverted, as other nodes don't have the state of older
+/// blocks anymore. The client is shut down, the blocks after `block_number` are deleted, and a new
+/// client is started with the same configuration. Its handle is written to the `void*` pointed by
+/// `parity`; the old handle becomes invalid, and so do its subscriptions and callbacks, as with
+/// `parity_restart`.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNSUPPORTED` if the client can't be reverted, and
+/// `PARITY_ERROR_INVALID_VALUE` if `block_number` isn't older than the best block, in which cases
+/// the client keeps running. Returns `PARITY_ERROR_REVERT_FAILED` if the chain couldn't be
+/// reverted but the client was restarted, and another `ParityError` code on error. If the new
+/// client fails to start, the `void*` pointed by `parity` is set to NULL. `parity_last_error_message`
+/// tells why in all these cases.
+int parity_revert_to_block(void** parity, uint64_t block_number);
 
 /// Destroys the parity client created with `parity_start`.
 ///
-/// **Warning**: `parity_start` can return NULL if execution finished instantly, in which case you
-///					must not call this function.
+/// Must be called exactly once per client. Passing NULL or a client that was already destroyed
+/// does nothing, which makes this function suitable for wrappers such as .NET's `SafeHandle`.
 void parity_destroy(void* parity);
 
+/// Destroys the parity client created with `parity_start`, without blocking for longer than
+/// `timeout_ms` milliseconds.
+///
+/// The shutdown happens on a background thread. If it didn't complete before the timeout elapsed,
+/// this function returns anyway and the shutdown carries on in the background.
+///
+/// `out_clean` will receive 1 if the shutdown completed in time, and 0 otherwise.
+///
+/// Must be called exactly once per client. Returns 0 on success, whether the shutdown completed in
+/// time or not, and `PARITY_ERROR_NULL_POINTER` if `out_clean` is NULL, in which case the client
+/// isn't destroyed. Passing NULL or a client that was already destroyed does nothing apart from writing 0 to
+/// `out_clean`, and returns `PARITY_ERROR_NULL_POINTER` or `PARITY_ERROR_WRONG_HANDLE_TYPE`.
+int parity_destroy_with_timeout(void* parity, size_t timeout_ms, int* out_clean);
+
+/// Destroys the parity client created with `parity_start` without blocking: the shutdown happens
+/// on a background thread, and `callback` is called with `custom` from that thread once it has
+/// completed, for example to let an event loop know that the database is closed.
+///
+/// The handle is invalid as soon as this function is called, and must not be used anymore, even
+/// before the callback is called. `callback` may be NULL.
+///
+/// Must be called exactly once per client. Passing NULL or a client that was already destroyed
+/// only calls the callback, from the calling thread.
+void parity_shutdown_async(void* parity, void (*callback)(void* custom), void* custom);
+
+/// Returns 1 if `parity` is a client returned by `parity_start` that wasn't destroyed yet, and 0
+/// otherwise.
+int parity_client_is_valid(void const* parity);
+
 /// Performs an RPC request.
 ///
 /// Blocks the current thread until the request is finished. You are therefore encouraged to spawn
 /// a new thread for each RPC request that requires accessing the blockchain.
 ///
+/// The request fails with `PARITY_ERROR_TIMEOUT` if it takes longer than the timeout set with
+/// `parity_set_rpc_timeout`. The request is processed on the calling thread, so this function can
+/// be called from the callbacks of the asynchronous requests.
+///
 /// - `rpc` and `len` must contain the JSON string representing the RPC request.
 /// - `out_str` and `out_len` point to a buffer where the output JSON result will be stored. If the
-///	  buffer is not large enough, the function fails.
+///	  buffer is not large enough, the function fails with `PARITY_ERROR_BUFFER_TOO_SMALL`.
 /// - `out_len` will receive the final length of the string.
-/// - On success, the function returns 0. On failure, it returns 1.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code.
+///
+/// ## Thread safety
+///
+/// Several threads can perform requests through the same client concurrently, as with all the
+/// other functions taking a client, except for the functions destroying it.
 ///
 /// **Important**: Keep in mind that this function doesn't write any null terminator on the output
 ///                string.
 ///
 int parity_rpc(void* parity, const char* rpc, size_t len, char* out_str, size_t* out_len);
 
+/// Performs an RPC request, giving up after a timeout.
+///
+/// Blocks the current thread until the request is finished or `timeout_ms` milliseconds have
+/// elapsed. If `timeout_ms` is 0, the timeout set with `parity_set_rpc_timeout` is used instead.
+/// The request is processed on the calling thread, so this function can be called from the
+/// callbacks of the asynchronous requests.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - `out_buf` and `out_buf_len` point to a buffer where the output JSON result will be stored.
+/// - `out_written` will receive the length of the response, even if the buffer is too small to
+///   hold it.
+/// - On success, the function returns 0. If the buffer is not large enough, it returns
+///   `PARITY_ERROR_BUFFER_TOO_SMALL` and nothing is written to `out_buf`; you can then retry with
+///   a buffer of `*out_written` bytes. If the timeout elapses, it returns `PARITY_ERROR_TIMEOUT`.
+///   On any other failure, it returns the corresponding `ParityError` code.
+///
+/// **Important**: Keep in mind that this function doesn't write any null terminator on the output
+///                string.
+///
+int parity_rpc_sync(void* parity, const char* rpc, size_t len, size_t timeout_ms, char* out_buf, size_t out_buf_len, size_t* out_written);
+
+/// Performs an RPC request in the background, and returns a token that can abort it. This
+/// function returns immediately.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - `callback` is called once with the value of `custom`, then the string pointer and length of
+///   the JSON response. If the request didn't produce any response before the timeout set with
+///   `parity_set_rpc_timeout` elapsed, the callback receives NULL and a length of 0.
+/// - `out_token` will receive a token that must be passed to `parity_rpc_abort` exactly once,
+///   whether the request completed or not.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code.
+///
+/// ## Thread safety
+///
+/// The callback is called from a background thread.
+///
+/// **Important**: Keep in mind that the string passed to the callback is not null-terminated.
+///
+int parity_rpc_cancelable(void* parity, const char* rpc, size_t len, void (*callback)(void* custom, const char* response, size_t response_len), void* custom, void** out_token);
+
+/// Aborts a request made with `parity_rpc_cancelable` if it is still in progress, and destroys the
+/// token.
+///
+/// The callback of an aborted request is never called, unless it was already running when this
+/// function was called. Aborting a request that already completed does nothing.
+///
+/// **Warning**: The token must not be used anymore after this function returns.
+void parity_rpc_abort(void* token);
+
+/// Starts an RPC request that the caller then drives with `parity_rpc_poll`, for example from the
+/// event loop of the host application. This function returns immediately.
+///
+/// Returns a handle to pass to `parity_rpc_poll` and `parity_rpc_end`, or NULL if `parity` isn't
+/// a client or the request isn't valid UTF-8.
+///
+/// Nothing signals the progress of the request: the caller must call `parity_rpc_poll` again until
+/// it completes, for example on every iteration of its event loop or from a timer. The request
+/// times out after the timeout set with `parity_set_rpc_timeout`, which `parity_rpc_poll` reports
+/// the first time it is called past it.
+///
+/// **Important**: `rpc` doesn't need to be null-terminated.
+void* parity_rpc_begin(void* parity, const char* rpc, size_t len);
+
+/// Advances a request started with `parity_rpc_begin`, without blocking.
+///
+/// - `out_done` will receive 1 if the request completed, and 0 if it is still in progress and
+///   this function must be called again later.
+/// - Once the request completed, `out_ptr` and `out_len` will receive the string pointer and
+///   length of the JSON response. The response stays valid until `parity_rpc_end` is called. While
+///   the request is in progress, they receive NULL and 0.
+/// - Returns 0 on success, `PARITY_ERROR_RPC_FAILED` if the request completed without producing
+///   any response, `PARITY_ERROR_TIMEOUT` if it timed out, and `PARITY_ERROR_WRONG_HANDLE_TYPE` if
+///   `handle` wasn't returned by `parity_rpc_begin`. Both a failed and a timed out request count
+///   as completed.
+///
+/// **Important**: Keep in mind that the response is not null-terminated.
+int parity_rpc_poll(void* handle, const char** out_ptr, size_t* out_len, int* out_done);
+
+/// Destroys a handle returned by `parity_rpc_begin`, abandoning the request if it didn't complete.
+///
+/// Does nothing if `handle` is NULL. Otherwise, must be called exactly once per handle.
+void parity_rpc_end(void* handle);
+
+/// Performs an RPC request in the background, passing a context that the caller can release once
+/// it isn't needed anymore. This function returns immediately.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - `callback` is called once with the value of `context`, then the string pointer and length of
+///   the JSON response. If the request didn't produce any response before the timeout set with
+///   `parity_set_rpc_timeout` elapsed, the callback receives NULL and a length of 0.
+/// - `release_context`, if not NULL, is called exactly once with the value of `context` after
+///   `callback` returned. If this function fails, `release_context` is called before it returns.
+///   This lets Swift and Objective-C callers balance a manual retain of the context.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code.
+///
+/// Only available if the library was compiled with the `swift` feature.
+///
+/// ## Thread safety
+///
+/// `callback` and `release_context` are called from a background thread.
+///
+/// **Important**: Keep in mind that the string passed to the callback is not null-terminated.
+///
+int parity_rpc_with_context(void* parity, const char* rpc, size_t len, void (*callback)(void* context, const char* response, size_t response_len), void* context, void (*release_context)(void* context));
+
+/// Performs a batch of RPC requests in the background.
+///
+/// All the requests are processed concurrently by a background runtime shared by all the requests
+/// of this client. This function returns immediately.
+///
+/// - `rpcs` and `rpc_lens` are the string pointers and lengths of the JSON requests. They must have
+///   a length equal to `count`. The strings don't need to be zero-terminated. They can be NULL if
+///   `count` is 0, in which case the callback is never called.
+/// - `callback` is called once per request. The first parameter of the callback is the value of
+///   `custom`, the second one is the index of the request within `rpcs`, and the last two are the
+///   string pointer and length of the JSON response. If a request didn't produce any response
+///   before the timeout set with `parity_set_rpc_timeout` elapsed, the callback receives NULL and
+///   a length of 0.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code and none of
+///   the requests are performed.
+///
+/// ## Thread safety
+///
+/// The callback is called from background threads, in the order in which responses are ready.
+/// Calls for different requests of the same batch may happen concurrently.
+///
+/// **Important**: Keep in mind that the strings passed to the callback are not null-terminated.
+///
+int parity_rpc_batch(void* parity, char const* const* rpcs, size_t const* rpc_lens, size_t count, void (*callback)(void* custom, size_t index, const char* response, size_t response_len), void* custom);
+
+/// Performs an RPC request in the background, handing the response over as raw bytes.
+///
+/// This function returns immediately. The request is processed by a background runtime shared by
+/// all the requests of this client.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - `callback` is called once, from a background thread, with `custom`, an error code, and the
+///   pointer and length of the response bytes. The error code is 0 when the request produced a
+///   response, in which case the bytes are exactly those of the response, without any conversion
+///   or null terminator. Otherwise it is `PARITY_ERROR_TIMEOUT` if no response was produced
+///   before the timeout set with `parity_set_rpc_timeout` elapsed, or `PARITY_ERROR_RPC_FAILED`,
+///   and the callback receives NULL and a length of 0.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code and the
+///   request isn't performed.
+///
+/// The response bytes are only valid during the call of the callback.
+int parity_rpc_raw(void* parity, const char* rpc, size_t len, void (*callback)(void* custom, int error, const uint8_t* response, size_t response_len), void* custom);
+
+/// Performs an RPC request in the background, telling JSON-RPC errors apart from successful
+/// responses.
+///
+/// This function returns immediately. The request is processed by a background runtime shared by
+/// all the requests of this client.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - `callback` is called once, from a background thread, with `custom`, an error code, an error
+///   flag, and the string pointer and length of the response.
+///   - If the request produced a response, the error code is 0. The error flag is 1 if the
+///     response is a JSON-RPC error object, for example because the method doesn't exist or its
+///     parameters are invalid, and 0 otherwise. The responses to batch requests always have an
+///     error flag of 0, as they may contain both successes and errors.
+///   - Otherwise, the error code is `PARITY_ERROR_TIMEOUT` if no response was produced before the
+///     timeout set with `parity_set_rpc_timeout` elapsed, or `PARITY_ERROR_RPC_FAILED`, for
+///     example for a notification, which doesn't get any response. The error flag is 0 and the
+///     callback receives NULL and a length of 0.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code and the
+///   request isn't performed.
+///
+/// **Important**: Keep in mind that the string passed to the callback is not null-terminated.
+///
+int parity_rpc_checked(void* parity, const char* rpc, size_t len, void (*callback)(void* custom, int error, int is_error, const char* response, size_t response_len), void* custom);
+
+/// Performs an RPC request in the background, echoing a tag chosen by the caller back to the
+/// callback. This lets several requests share a callback without parsing the id of the responses.
+///
+/// This function returns immediately. The request is processed by a background runtime shared by
+/// all the requests of this client.
+///
+/// - `rpc` and `len` must contain the JSON string representing the RPC request.
+/// - `callback` is called once, from a background thread, with `custom`, the value of `tag`, an
+///   error code, and the string pointer and length of the response. The error code is 0 when the
+///   request produced a response. Otherwise it is `PARITY_ERROR_TIMEOUT` if no response was
+///   produced before the timeout set with `parity_set_rpc_timeout` elapsed, or
+///   `PARITY_ERROR_RPC_FAILED`, and the callback receives NULL and a length of 0.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code and the
+///   request isn't performed.
+///
+/// **Important**: Keep in mind that the string passed to the callback is not null-terminated.
+///
+int parity_rpc_tagged(void* parity, const char* rpc, size_t len, uint64_t tag, void (*callback)(void* custom, uint64_t tag, int error, const char* response, size_t response_len), void* custom);
+
+/// Sets the timeout of the RPC requests subsequently made through this client, in milliseconds.
+///
+/// Defaults to 5 minutes.
+void parity_set_rpc_timeout(void* parity, size_t timeout_ms);
+
+/// Sets the number of worker threads of the background runtime that processes the RPC requests of
+/// a client, for example 1 on single-core devices. 0 restores the default of one thread per CPU
+/// core.
+///
+/// Each client creates its runtime when it performs its first RPC request, and the runtime keeps
+/// its number of threads afterwards. This function must therefore be called before the first
+/// request of the clients it should apply to, ideally before starting them. It applies to all the
+/// clients of the process.
+void parity_set_rpc_thread_count(size_t thread_count);
+
+/// Retrieves the number of transactions of the transaction queue, as shown by the
+/// `parity_pendingTransactions` and `parity_allTransactions` RPCs.
+///
+/// `out_pending` receives the number of transactions that are ready to be included in the next
+/// block, and `out_queued` the number of the other ones, which wait for a transaction with a lower
+/// nonce. The queue of a light client only holds the transactions submitted through it.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_txqueue_status(void* parity, size_t* out_pending, size_t* out_queued);
+
+/// Retrieves the state of a transaction from the chain and the transaction queue, to follow a
+/// transaction until it is mined without several RPC queries.
+///
+/// - `hash` must point to the 32 bytes of the hash of the transaction.
+/// - `out_status` will receive a `ParityTransactionStatus`. Transactions that didn't go through
+///   the client are unknown once they leave the transaction queue without being mined, rather
+///   than dropped.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on
+/// error.
+int parity_transaction_status(void* parity, const uint8_t* hash, int* out_status);
+
+/// Submits a raw signed transaction to the transaction queue, performing the same checks as the
+/// `eth_sendRawTransaction` RPC.
+///
+/// - `rlp` and `len` must contain the RLP-encoded signed transaction.
+/// - `callback` is called exactly once, before this function returns. The first parameter of the
+///   callback is the value of `custom`. If the transaction was accepted, `hash` points to the 32
+///   bytes of its hash and `error` is NULL. Otherwise, `hash` is NULL and `error` and `error_len`
+///   describe why the transaction was rejected. The pointers are only valid during the call.
+/// - The function returns 0 if the callback was called, and a `ParityError` code otherwise.
+///
+/// **Important**: Keep in mind that the error string passed to the callback is not
+/// null-terminated.
+///
+int parity_send_raw_transaction(void* parity, const uint8_t* rlp, size_t len, void (*callback)(void* custom, const uint8_t* hash, const char* error, size_t error_len), void* custom);
+
+/// Lists the accounts managed by the client, as `personal_listAccounts` does over RPC.
+///
+/// `callback` is called once per account, from the calling thread and before the function
+/// returns, with `custom` and a pointer to the 20 bytes of the address. The pointer is only valid
+/// during the call.
+///
+/// Returns 0 on success, `PARITY_ERROR_NULL_CALLBACK` if `callback` is NULL,
+/// `PARITY_ERROR_ACCOUNTS_UNAVAILABLE` if the key store couldn't be read, and a `ParityError` code
+/// on error.
+int parity_list_accounts(void* parity, void (*callback)(void* custom, const uint8_t* address), void* custom);
+
+/// Unlocks an account managed by the client, so that it signs transactions and messages without
+/// asking for its password, as `personal_unlockAccount` does over RPC without requiring the
+/// `--geth` flag.
+///
+/// - `address` must point to the 20 bytes of the address of the account.
+/// - `password` and `password_len` must contain the UTF-8 password of the account, which may be
+///   empty.
+/// - The account stays unlocked for `duration_secs` seconds, or until the client stops if
+///   `duration_secs` is 0.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_PASSWORD` if the password doesn't match,
+/// `PARITY_ERROR_UNKNOWN_ACCOUNT` if the client doesn't manage the account,
+/// `PARITY_ERROR_ACCOUNTS_UNAVAILABLE` if the key store couldn't be read, and a `ParityError` code
+/// on error.
+int parity_unlock_account(void* parity, const uint8_t* address, const char* password, size_t password_len, uint64_t duration_secs);
+
+/// Imports a raw private key into the key store of the client, as `parity_newAccountFromSecret`
+/// does over RPC, for example to use deterministic accounts funded by the chain spec in tests.
+///
+/// - `secret` must point to the 32 bytes of the private key.
+/// - `password` and `password_len` contain the UTF-8 password encrypting the key file, which may
+///   be empty. The account is locked until unlocked with `parity_unlock_account`.
+/// - `out_address` must point to a buffer of 20 bytes, which receives the address of the account.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if the bytes aren't a valid private key,
+/// `PARITY_ERROR_ACCOUNTS_UNAVAILABLE` if the key couldn't be written to the key store, and a
+/// `ParityError` code on error.
+int parity_import_private_key(void* parity, const uint8_t* secret, const char* password, size_t password_len, uint8_t* out_address);
+
+/// Exports the key file of an account managed by the client, as `parity_exportAccount` does over
+/// RPC, for example to back it up. The key file is the encrypted JSON of the key store, which the
+/// other Ethereum clients can import as well.
+///
+/// - `address` must point to the 20 bytes of the address of the account.
+/// - `password` and `password_len` must contain the UTF-8 password of the account, which may be
+///   empty.
+/// - `out_ptr` will receive a pointer to the null-terminated JSON string and `out_len` its length,
+///   not counting the null terminator. The string must be freed with `parity_string_destroy`.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_PASSWORD` if the password doesn't match,
+/// `PARITY_ERROR_UNKNOWN_ACCOUNT` if the client doesn't manage the account,
+/// `PARITY_ERROR_ACCOUNTS_UNAVAILABLE` if the key store couldn't be read, and a `ParityError` code
+/// on error.
+int parity_export_account(void* parity, const uint8_t* address, const char* password, size_t password_len, char** out_ptr, size_t* out_len);
+
+/// Imports a key file into the key store of the client, as `parity_newAccountFromWallet` does
+/// over RPC, for example to restore an account exported with `parity_export_account`.
+///
+/// - `json` and `len` must contain the JSON key file, which doesn't need to be null-terminated.
+/// - `password` and `password_len` contain the UTF-8 password the key file is encrypted with,
+///   which may be empty. The imported account keeps it, and is locked until unlocked with
+///   `parity_unlock_account`.
+/// - `out_address` must point to a buffer of 20 bytes, which receives the address of the account.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `json` isn't a valid key file,
+/// `PARITY_ERROR_INVALID_PASSWORD` if the password doesn't decrypt it,
+/// `PARITY_ERROR_ACCOUNTS_UNAVAILABLE` if the key couldn't be written to the key store, and a
+/// `ParityError` code on error.
+int parity_import_keystore(void* parity, const char* json, size_t len, const char* password, size_t password_len, uint8_t* out_address);
+
+/// Signs a message with an unlocked account managed by the client, as `eth_sign` does over RPC.
+/// The signed hash is the Keccak-256 of the message prefixed with
+/// `"\x19Ethereum Signed Message:\n"` and the decimal length of the message.
+///
+/// - `address` must point to the 20 bytes of the address of the account.
+/// - `message` and `message_len` contain the bytes of the message. `message` may be NULL if
+///   `message_len` is 0.
+/// - `out_signature` must point to a buffer of 65 bytes, which receives the `r` and `s` values of
+///   the signature followed by `v`, which is 27 or 28.
+///
+/// Returns 0 on success, `PARITY_ERROR_ACCOUNT_LOCKED` if the account isn't unlocked,
+/// `PARITY_ERROR_UNKNOWN_ACCOUNT` if the client doesn't manage the account,
+/// `PARITY_ERROR_ACCOUNTS_UNAVAILABLE` if the key store couldn't be used, and a `ParityError` code
+/// on error.
+int parity_sign(void* parity, const uint8_t* address, const uint8_t* message, size_t message_len, uint8_t* out_signature);
+
+/// Sets the author of the blocks sealed by the client, as with the `--author` command line option.
+///
+/// `address` must point to the 20 bytes of the address.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNSUPPORTED` on light clients, and a `ParityError` code on
+/// error.
+int parity_set_author(void* parity, const uint8_t* address);
+
+/// Sets the minimal gas price of the transactions accepted by the transaction queue, as with the
+/// `--min-gas-price` command line option. This replaces the automatic gas price calibration if
+/// it was enabled.
+///
+/// `wei` and `len` contain the gas price in wei, as a little-endian integer of at most 32 bytes.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `len` is greater than 32,
+/// `PARITY_ERROR_UNSUPPORTED` on light clients, and a `ParityError` code on error.
+int parity_set_min_gas_price(void* parity, const uint8_t* wei, size_t len);
+
+/// Seals a new block right away, even if there are no pending transactions, for example to
+/// include the transactions that were just submitted on a development chain.
+///
+/// `out_block_number` will receive the number of the new block.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNSUPPORTED` if the engine of the chain doesn't seal
+/// blocks on demand like the instant seal engine of `--chain dev`, `PARITY_ERROR_SEAL_FAILED` if
+/// no block could be sealed, and a `ParityError` code on error.
+int parity_dev_mine_block(void* parity, uint64_t* out_block_number);
+
+/// Seals `count` blocks in a row, as `parity_dev_mine_block` does, for example to advance a
+/// development chain quickly in tests. The pending transactions go into the first block.
+///
+/// `out_final_block` will receive the number of the last block sealed, which is also set if
+/// sealing fails, to the last block that was sealed before.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `count` is 0,
+/// `PARITY_ERROR_UNSUPPORTED` if the engine of the chain doesn't seal blocks on demand like the
+/// instant seal engine of `--chain dev`, `PARITY_ERROR_SEAL_FAILED` if a block couldn't be sealed,
+/// and a `ParityError` code on error.
+int parity_dev_mine_blocks(void* parity, uint64_t count, uint64_t* out_final_block);
+
+/// Submits an RLP-encoded block to the import queue of the client, as if it had been received
+/// from the network. Light clients only import the header of the block.
+///
+/// The block is imported in the background: use `parity_on_new_block` to find out when it is part
+/// of the chain.
+///
+/// - `out_result` will receive one of the `ParityBlockImport` values.
+/// - Returns 0 on success, including when the block is rejected, and a `ParityError` code on
+///   error.
+///
+int parity_import_block(void* parity, const uint8_t* rlp, size_t len, int* out_result);
+
+/// Subscribes to notifications through a PubSub RPC request, such as `eth_subscribe`.
+///
+/// - `rpc` and `len` must contain the JSON string representing the subscription request, for
+///   example `{"method":"eth_subscribe","params":["newHeads"],"id":1,"jsonrpc":"2.0"}`.
+/// - `callback` is first called with the JSON response to the subscription request itself, then
+///   once for every notification. The first parameter of the callback is the value of `custom`.
+///   The second and third parameters are the string pointer and length of the JSON message.
+/// - `out_sub_id` will receive an identifier to pass to `parity_unsubscribe`.
+/// - On success, the function returns 0. It returns `PARITY_ERROR_TIMEOUT` if the request takes
+///   longer than the timeout set with `parity_set_rpc_timeout`, and `PARITY_ERROR_RPC_FAILED` if
+///   the response has no result, in which case the callback isn't called and
+///   `parity_last_error_message` returns the message of the JSON-RPC error. On any other failure,
+///   it returns the corresponding `ParityError` code.
+///
+/// ## Thread safety
+///
+/// Notifications are delivered from a background thread dedicated to the subscription, named after
+/// the method of the request, such as `rpc-eth_subscribe`. Up to 16 notifications wait for the
+/// callback to return, after which the following ones wait as well;
+/// `parity_set_subscription_buffer` changes that.
+///
+/// **Important**: Keep in mind that the strings passed to the callback are not null-terminated.
+///
+int parity_subscribe(void* parity, const char* rpc, size_t len, void (*callback)(void* custom, const char* msg, size_t msg_len), void* custom, size_t* out_sub_id);
+
+/// Sets how many notifications of a subscription created with `parity_subscribe` can wait for
+/// the callback, and what happens to the following ones when the callback can't keep up.
+///
+/// - `max` is the maximum number of notifications in the buffer, 16 by default.
+/// - `policy` is a `ParitySubscriptionBufferPolicy`.
+/// - `on_dropped` can be NULL. Otherwise, when notifications were dropped, it is called with the
+///   value of `custom` and their number right before the callback of the subscription gets the
+///   next notification, from the same thread.
+///
+/// Switching to `PARITY_SUBSCRIPTION_BUFFER_DROP_OLDEST` immediately drops the oldest
+/// notifications beyond `max`, and switching to `PARITY_SUBSCRIPTION_BUFFER_DROP_NEWEST` the
+/// newest ones.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `sub_id` isn't a subscription of the
+/// client, `max` is 0 or `policy` is unknown, and a `ParityError` code on error.
+int parity_set_subscription_buffer(void* parity, size_t sub_id, size_t max, int policy, void (*on_dropped)(void* custom, size_t count), void* custom);
+
+/// Cancels a subscription created with `parity_subscribe`.
+///
+/// Notifications that are still queued are discarded. If the callback is delivering a
+/// notification, this function waits for it to return, so that the callback is never called once
+/// this function has returned. `parity_destroy` does the same for the remaining subscriptions.
+/// The callback can cancel its own subscription.
+void parity_unsubscribe(void* parity, size_t sub_id);
+
+/// Registers a callback called whenever a new block is added to the chain.
+///
+/// - `callback` is called with the value of `custom`, the number of the new block, and a pointer
+///   to the 32 bytes of its hash. The pointer is only valid during the call. When the chain
+///   advances by several blocks at once, the callback is called once per block, in order.
+/// - `out_id` will receive an identifier to pass to `parity_remove_new_block_listener`.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code.
+///
+/// ## Thread safety
+///
+/// The callback is called from the thread that imported the block, and must return quickly as it
+/// delays the following imports.
+///
+int parity_on_new_block(void* parity, void (*callback)(void* custom, uint64_t number, const uint8_t* hash), void* custom, size_t* out_id);
+
+/// Unregisters a callback registered with `parity_on_new_block`.
+///
+/// A call to the callback that was already in progress may still complete after this function
+/// returns.
+void parity_remove_new_block_listener(void* parity, size_t id);
+
+/// Registers a callback called with the logs matching a filter, as the `eth_subscribe` RPC does
+/// with the `logs` subscription, without encoding the logs as JSON.
+///
+/// - `filter` and `len` contain the filter as a JSON object, in the same format as for
+///   `eth_subscribe`, such as `{"address":"0x...","topics":["0x..."]}`. The block range of the
+///   filter is ignored: the logs are those of the blocks added to or removed from the chain after
+///   the subscription.
+/// - `callback` is called with the value of `custom` and a pointer to the log, which is only
+///   valid during the call. The logs of a block are passed in order.
+/// - `out_sub_id` will receive an identifier to pass to `parity_unsubscribe_logs`.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if the filter is invalid,
+/// `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on error.
+///
+/// ## Thread safety
+///
+/// The callback is called from the thread that imported the block, and must return quickly as it
+/// delays the following imports.
+///
+int parity_subscribe_logs(void* parity, const char* filter, size_t len, void (*callback)(void* custom, const struct ParityLog* log), void* custom, size_t* out_sub_id);
+
+/// Cancels a subscription created with `parity_subscribe_logs`.
+///
+/// A call to the callback that was already in progress may still complete after this function
+/// returns.
+void parity_unsubscribe_logs(void* parity, size_t sub_id);
+
+/// Retrieves the synchronization status of the client.
+///
+/// - `out_current_block` will receive the number of the best block imported locally.
+/// - `out_highest_block` will receive the number of the highest block seen on the network, or
+///   the same value as `out_current_block` if no peer advertised a higher block.
+/// - `out_is_syncing` will receive 1 if the client is performing a major sync, and 0 otherwise.
+/// - On success, the function returns 0. It returns `PARITY_ERROR_UNAVAILABLE` if the client
+///   doesn't sync because its network isn't running, for example in offline mode or after
+///   `parity_network_pause`, in which case nothing is written. On any other failure, it returns a
+///   `ParityError` code.
+///
+int parity_sync_status(void* parity, uint64_t* out_current_block, uint64_t* out_highest_block, int* out_is_syncing);
+
+/// Registers a callback reporting the progress of the synchronization every `interval_ms`
+/// milliseconds, for example to display a progress bar. Replaces the callback registered earlier,
+/// if any.
+///
+/// - `callback` is called with the value of `custom`, the number of the best block imported
+///   locally, the number of the highest block seen on the network, and the average number of
+///   blocks imported per second since the previous call. Passing NULL removes the callback.
+/// - Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `interval_ms` is 0, and a `ParityError`
+///   code on error.
+///
+/// ## Thread safety
+///
+/// The callback is called from a background thread. A call that was already in progress may still
+/// complete after the callback was removed.
+///
+int parity_set_sync_progress_callback(void* parity, void (*callback)(void* custom, uint64_t current_block, uint64_t highest_block, double blocks_per_sec), void* custom, size_t interval_ms);
+
+/// Retrieves the health of the node, from the same information as the `parity_nodeStatus` RPC
+/// but without any JSON.
+///
+/// - `out_status` will receive a combination of the `ParityNodeHealth` bits. Exactly one of
+///   `PARITY_NODE_HEALTH_PEERS_OK` and `PARITY_NODE_HEALTH_NO_PEERS` is always set.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code.
+///
+/// The node is considered healthy if `PARITY_NODE_HEALTH_SYNCING` isn't set, and either
+/// `PARITY_NODE_HEALTH_PEERS_OK` or `PARITY_NODE_HEALTH_PEERS_CHECK_DISABLED` is set.
+///
+int parity_node_health(void* parity, uint32_t* out_status);
+
+/// Retrieves metrics of the client in the Prometheus text exposition format, so that they can be
+/// served by the HTTP server of the embedding application. All the metrics are gauges:
+///
+/// - `parity_chain_best_block`: number of the best block imported locally.
+/// - `parity_sync_highest_block`: number of the highest block seen on the network.
+/// - `parity_sync_is_syncing`: 1 if the client is performing a major sync, and 0 otherwise.
+/// - `parity_network_peers`: number of connected peers.
+/// - `parity_txqueue_pending` and `parity_txqueue_queued`: see `parity_txqueue_status`.
+///
+/// `out_ptr` will receive a pointer to the null-terminated string and `out_len` its length, not
+/// counting the null terminator. The string must be freed with `parity_string_destroy`.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_metrics_prometheus(void* parity, char** out_ptr, size_t* out_len);
+
+/// Blocks the calling thread until the node is healthy, as reported by `parity_node_health`, or
+/// until `timeout_ms` milliseconds elapsed.
+///
+/// - `out_synced` will receive 1 if the node is healthy, that is not performing a major sync and
+///   connected to peers unless the chain doesn't require any, and 0 if the timeout elapsed first.
+/// - On success, the function returns 0. On failure, it returns a `ParityError` code.
+///
+/// A node that hasn't found any peer yet isn't considered synced, even though it isn't syncing.
+///
+int parity_wait_for_sync(void* parity, size_t timeout_ms, int* out_synced);
+
+/// Retrieves the number of peers the client is connected to.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_peer_count(void* parity, size_t* out_count);
+
+/// Lists the peers the client is connected to, with the information of `parity_netPeers` that
+/// doesn't require parsing JSON.
+///
+/// `callback` is called once per peer, from the calling thread and before the function returns,
+/// with `custom` and a pointer to the information of the peer, which is only valid during the
+/// call.
+///
+/// Returns 0 on success, `PARITY_ERROR_NULL_CALLBACK` if `callback` is NULL, and a `ParityError`
+/// code on error.
+int parity_peers(void* parity, void (*callback)(void* custom, const struct ParityPeerInfo* peer), void* custom);
+
+/// Stops all the peer-to-peer activity of the client, for example while a mobile application is
+/// in the background. All peers are disconnected, but the client, its database and the RPC
+/// interfaces stay available. Pausing an already paused network does nothing.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_network_pause(void* parity);
+
+/// Restarts the peer-to-peer activity of the client after `parity_network_pause`. Failing to bind
+/// the network port is logged, but isn't reported as an error. Resuming a network that isn't
+/// paused does nothing, so this doesn't start the network of a client in offline mode.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_network_resume(void* parity);
+
+/// Changes the maximum number of incoming and outgoing peers of the running client, without
+/// restarting it.
+///
+/// The client keeps opening connections until `max_out` peers are connected, and accepts up to
+/// `max_in` incoming connections, or half of `max_out` if that is larger. Peers above the new
+/// limits are disconnected, except for reserved peers.
+///
+/// These limits replace both `--min-peers` and `--max-peers`: the minimum number of peers becomes
+/// `max_out`, and the maximum `max_out + max_in`. `--min-peers` therefore has no effect once this
+/// function has been called.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_set_peer_limits(void* parity, uint32_t max_in, uint32_t max_out);
+
+/// Retrieves the number and hash of the best block of the chain, which is cheaper to poll than the
+/// `eth_blockNumber` RPC.
+///
+/// `out_hash` must point to a buffer of 32 bytes, which receives the hash of the block. Light
+/// clients report the best header they imported.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_best_block(void* parity, uint64_t* out_number, uint8_t* out_hash);
+
+/// Retrieves the kind of client, from the `--light` and `--pruning` settings it was started with,
+/// to know which state queries it can answer.
+///
+/// `out_mode` will receive one of the `ParityClientMode` values.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_client_mode(void* parity, int* out_mode);
+
+/// Retrieves the chain id used to sign transactions as per EIP-155, as returned by the
+/// `eth_chainId` RPC.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNAVAILABLE` if replay protection isn't active at the best
+/// block yet, and a `ParityError` code on error.
+int parity_chain_id(void* parity, uint64_t* out_chain_id);
+
+/// Retrieves the id of the network the client connects to, as returned by the `net_version` RPC.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_network_id(void* parity, uint64_t* out_network_id);
+
+/// Retrieves the name of the latest Ethereum hard fork whose EVM rules apply at the best block,
+/// which is one of `frontier`, `homestead`, `tangerine_whistle`, `spurious_dragon`, `byzantium`
+/// and `constantinople`. The name is derived from the rules themselves rather than from the block
+/// numbers of the chain spec, so that it applies to any chain.
+///
+/// `out_ptr` will receive a pointer to the null-terminated string and `out_len` its length, not
+/// counting the null terminator. The string must be freed with `parity_string_destroy`.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_active_fork(void* parity, char** out_ptr, size_t* out_len);
+
+/// Retrieves the median gas price of the transactions of the recent blocks, as shown by the
+/// `parity_gasPriceHistogram` RPC.
+///
+/// `out_wei` must point to a buffer of 32 bytes, which receives the gas price in wei as a
+/// big-endian integer.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNAVAILABLE` if the recent blocks have no transactions, or
+/// on light clients if the gas prices weren't fetched from the network in the last few hours, and
+/// a `ParityError` code on error.
+int parity_gas_price_median(void* parity, uint8_t* out_wei);
+
+/// Retrieves the given percentile, between 0 and 100, of the gas prices of the transactions of the
+/// recent blocks. `out_wei` and the return value are the same as with `parity_gas_price_median`,
+/// except that `PARITY_ERROR_INVALID_VALUE` is returned if `percentile` is greater than 100.
+int parity_gas_price_percentile(void* parity, size_t percentile, uint8_t* out_wei);
+
+/// Retrieves the fees to offer for a new transaction, split as with EIP-1559 between the base fee
+/// and the priority fee, or tip, paid to the miner.
+///
+/// EIP-1559 isn't supported, so `out_base_fee` receives the gas price the `eth_gasPrice` RPC
+/// suggests, that is the median gas price of the recent blocks, or on full clients the minimal
+/// gas price of the miner if they have no transactions, and `out_tip` receives zero. Both must
+/// point to buffers of 32 bytes, which receive the amounts in wei as big-endian integers.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNAVAILABLE` on light clients if the gas prices weren't
+/// fetched from the network in the last few hours, and a `ParityError` code on error.
+int parity_suggested_fees(void* parity, uint8_t* out_base_fee, uint8_t* out_tip);
+
+/// Retrieves the balance of an account in the state of the best block, as returned by the
+/// `eth_getBalance` RPC, without going through JSON-RPC.
+///
+/// `address` must point to the 20 bytes of the address of the account, and `out_wei` to a buffer
+/// of 32 bytes, which receives the balance in wei as a big-endian integer.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNAVAILABLE` if the state was pruned,
+/// `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on error.
+int parity_get_balance(void* parity, const uint8_t* address, uint8_t* out_wei);
+
+/// Retrieves a value of the storage of an account in the state of the best block, as returned by
+/// the `eth_getStorageAt` RPC, without going through JSON-RPC.
+///
+/// `address` must point to the 20 bytes of the address of the account, `key` to the 32 bytes of
+/// the storage position, and `out_value` to a buffer of 32 bytes, which receives the value.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNAVAILABLE` if the state was pruned,
+/// `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on error.
+int parity_get_storage_at(void* parity, const uint8_t* address, const uint8_t* key, uint8_t* out_value);
+
+/// Executes a read-only call of a contract against the state of the best block, as the
+/// `eth_call` RPC does when only `to` and `data` are given, without going through JSON-RPC.
+///
+/// - `to` must point to the 20 bytes of the address of the contract, and `data` and `data_len`
+///   contain the input of the call, such as an ABI-encoded function call. `data` may be NULL if
+///   `data_len` is 0.
+/// - `callback` is called once, before this function returns and from the calling thread, with
+///   `custom`, an error code, and the pointer and length of the output. The error code is 0 if the
+///   call succeeded, and `PARITY_ERROR_CALL_REVERTED` if it reverted, in which case the output
+///   usually holds the ABI-encoded revert reason.
+/// - Returns 0 if the callback was called, `PARITY_ERROR_CALL_FAILED` if the call failed for
+///   another reason or the state was pruned, `PARITY_ERROR_UNSUPPORTED` for light clients, and a
+///   `ParityError` code on error. The callback isn't called in these cases.
+///
+/// The output bytes are only valid during the call of the callback.
+int parity_eth_call(void* parity, const uint8_t* to, const uint8_t* data, size_t data_len, void (*callback)(void* custom, int error, const uint8_t* output, size_t output_len), void* custom);
+
+/// Estimates the gas a transaction would use against the state of the best block, as the
+/// `eth_estimateGas` RPC does when neither the gas, its price nor the nonce are given, without
+/// going through JSON-RPC.
+///
+/// - `from` must point to the 20 bytes of the address of the sender, which doesn't need to be an
+///   account of the client.
+/// - `to` must point to the 20 bytes of the address of the recipient, or be NULL to estimate the
+///   creation of a contract, in which case `data` holds its init code.
+/// - `data` and `data_len` contain the input of the transaction. `data` may be NULL if `data_len`
+///   is 0.
+/// - `value` and `value_len` contain the amount of wei sent, as a little-endian integer of at most
+///   32 bytes. `value` may be NULL if `value_len` is 0.
+/// - On success, the estimate is written to `out_gas`.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `value_len` is greater than 32,
+/// `PARITY_ERROR_CALL_FAILED` if the transaction fails with any amount of gas, for example because
+/// it reverts, or if the state was pruned, `PARITY_ERROR_UNSUPPORTED` for light clients, and a
+/// `ParityError` code on error.
+int parity_estimate_gas(void* parity, const uint8_t* from, const uint8_t* to, const uint8_t* data, size_t data_len, const uint8_t* value, size_t value_len, uint64_t* out_gas);
+
+/// Reads a block of the canonical chain from the database, as the `eth_getBlockByNumber` RPC does,
+/// without encoding it as JSON.
+///
+/// - `number` is the number of the block.
+/// - The hashes of the transactions of the block are included if `include_txs` is non-zero.
+/// - `callback` is called once, before this function returns and from the calling thread, with
+///   `custom` and a pointer to the block, which is only valid during the call.
+///
+/// Returns 0 if the callback was called, `PARITY_ERROR_UNAVAILABLE` if the chain has no block with
+/// that number, `PARITY_ERROR_INVALID_VALUE` if the gas used or the gas limit of the block doesn't
+/// fit in 64 bits, `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on error.
+int parity_get_block(void* parity, uint64_t number, int include_txs, void (*callback)(void* custom, const struct ParityBlock* block), void* custom);
+
+/// Reads the receipt of a transaction from the receipts stored with the blocks, as the
+/// `eth_getTransactionReceipt` RPC does, without encoding it as JSON. Wallets waiting for a
+/// transaction to be mined can call this function repeatedly.
+///
+/// - `hash` must point to the 32 bytes of the hash of the transaction.
+/// - `callback` is called once, before this function returns and from the calling thread, with
+///   `custom` and a pointer to the receipt, which is only valid during the call. The pointer is
+///   NULL if the transaction isn't part of the canonical chain, for example because it is still
+///   pending.
+///
+/// Returns 0 if the callback was called, `PARITY_ERROR_INVALID_VALUE` if the gas used or the
+/// cumulative gas used of the receipt doesn't fit in 64 bits, `PARITY_ERROR_UNSUPPORTED` for light
+/// clients, and a `ParityError` code on error.
+int parity_get_receipt(void* parity, const uint8_t* hash, void (*callback)(void* custom, const struct ParityReceipt* receipt), void* custom);
+
+/// Replays a transaction of the chain, as the `trace_replayTransaction` RPC does, and hands its
+/// traces over in binary form instead of JSON. Tracing doesn't need to be enabled on the node, but
+/// the state of the block preceding the transaction must be available.
+///
+/// - `hash` must point to the 32 bytes of the hash of the transaction.
+/// - `callback` is called once, before this function returns and from the calling thread, with
+///   `custom` and the pointer and length of the traces. They're encoded as an RLP list with one
+///   item per call or contract creation, in execution order, as stored in the trace database of
+///   Parity: `[action, result, subtraces, trace_address]`. `action` is `[type, details]` with a
+///   type of 0 for calls, 1 for creations and 2 for self-destructs, and `result` is `[type,
+///   details]` with a type of 0 for successful calls, 1 for successful creations, 2 for failed
+///   calls and 3 for failed creations, or `[4]` if there's no result.
+/// - Returns 0 if the callback was called, `PARITY_ERROR_UNAVAILABLE` if the transaction is
+///   unknown or its state was pruned, `PARITY_ERROR_CALL_FAILED` if it couldn't be replayed,
+///   `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on error.
+///
+/// The trace bytes are only valid during the call of the callback.
+int parity_trace_transaction(void* parity, const uint8_t* hash, void (*callback)(void* custom, const uint8_t* traces, size_t traces_len), void* custom);
+
+/// Retrieves the size of the database of the client on disk, in bytes. Only the blockchain
+/// database is counted, not the key store, the snapshots or the other files of the data directory.
+///
+/// The size is computed by listing the database files on every call. It is 0 for in-memory
+/// clients, which keep no database files.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNAVAILABLE` if the size of the files couldn't be read, and
+/// a `ParityError` code on error.
+int parity_database_size(void* parity, uint64_t* out_bytes);
+
+/// Writes the buffered changes of the database of the client to disk, as happens when the client
+/// shuts down. Call this before copying the data directory, for example to back it up, while the
+/// client keeps running. Blocks written after the call are buffered again.
+///
+/// Returns 0 on success, `PARITY_ERROR_FLUSH_FAILED` if the changes couldn't be written, and a
+/// `ParityError` code on error.
+int parity_flush_database(void* parity);
+
+/// Retrieves the Merkle proofs of an account and of values in its storage against the state root
+/// of the best block, like the `eth_getProof` RPC of other clients, in binary form instead of JSON.
+///
+/// - `address` must point to the 20 bytes of the address of the account.
+/// - `storage_keys` must point to `key_count` consecutive storage keys of 32 bytes each, and may
+///   be NULL if `key_count` is 0.
+/// - `callback` is called once, before this function returns and from the calling thread, with
+///   `custom` and the pointer and length of the proofs, encoded as the RLP list `[account,
+///   account_proof, storage_proofs]`:
+///   - `account` is the list `[nonce, balance, storage_root, code_hash]` of the account, with
+///     default values if it doesn't exist;
+///   - `account_proof` is the list of the RLP-encoded trie nodes from the state root to the
+///     account;
+///   - `storage_proofs` is a list of `[key, value, proof]` entries, in the order of
+///     `storage_keys`, where `proof` is the list of the trie nodes from the storage root to the
+///     value, and `value` is zero if the key isn't set.
+/// - Returns 0 if the callback was called, `PARITY_ERROR_UNAVAILABLE` if the state of the best
+///   block was pruned, `PARITY_ERROR_UNSUPPORTED` for light clients, and a `ParityError` code on
+///   error. The callback isn't called in these cases.
+///
+/// The proof bytes are only valid during the call of the callback.
+int parity_get_proof(void* parity, const uint8_t* address, const uint8_t* storage_keys, size_t key_count, void (*callback)(void* custom, const uint8_t* proof, size_t len), void* custom);
+
+/// Takes a snapshot of the best block, as done periodically unless `--no-periodic-snapshot` is
+/// passed, and blocks until it has been written. The snapshot replaces the previous one in the
+/// `snapshot` directory of the database.
+///
+/// `out_path` will receive a pointer to the null-terminated path of the directory holding the
+/// snapshot and `out_len` its length, not counting the null terminator. The string must be freed
+/// with `parity_string_destroy`.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNSUPPORTED` for light clients,
+/// `PARITY_ERROR_SNAPSHOT_FAILED` if the snapshot couldn't be taken, for example because another
+/// one is in progress, and a `ParityError` code on any other error.
+int parity_take_snapshot(void* parity, char** out_path, size_t* out_len);
+
+/// Restores the snapshot at `path`, which must be either a directory returned by
+/// `parity_take_snapshot` or a file written by `parity snapshot`, and blocks until the restoration
+/// completes. The client keeps running meanwhile and continues from the restored block.
+///
+/// - `path` and `len` must contain the UTF-8 path of the snapshot.
+///
+/// Returns 0 on success, `PARITY_ERROR_UNSUPPORTED` for light clients,
+/// `PARITY_ERROR_SNAPSHOT_FAILED` if the snapshot couldn't be read or restored, and a `ParityError`
+/// code on any other error.
+int parity_restore_snapshot(void* parity, const char* path, size_t len);
+
+/// Retrieves the enode URL of the client, such as `enode://1a2b...@192.168.0.1:30303`.
+///
+/// `out_ptr` will receive a pointer to the null-terminated string and `out_len` its length, not
+/// counting the null terminator. The string must be freed with `parity_string_destroy`.
+///
+/// Returns 0 on success, `PARITY_ERROR_NETWORK_DISABLED` if the client has no network, and a
+/// `ParityError` code on any other error.
+int parity_enode(void* parity, char** out_ptr, size_t* out_len);
+
+/// Retrieves the public key of the node identity on the network, which is the id of its enode URL,
+/// for example to allow the node in a firewall or in the reserved peers of other nodes.
+///
+/// `out_public_key` must point to a buffer of 64 bytes, which will receive the uncompressed
+/// secp256k1 public key, without the `0x04` prefix.
+///
+/// Returns 0 on success, `PARITY_ERROR_NETWORK_DISABLED` if the client has no network or it is
+/// paused with `parity_network_pause`, and a `ParityError` code on any other error.
+int parity_node_public_key(void* parity, uint8_t* out_public_key);
+
+/// Adds a reserved peer to a running client, as the `parity_addReservedPeer` RPC does. The client
+/// connects to it and stays connected even above the peer limits.
+///
+/// `enode` and `len` contain the enode URL of the peer, which doesn't need to be zero-terminated.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `enode` isn't a valid enode URL, and a
+/// `ParityError` code on error.
+int parity_add_reserved_peer(void* parity, const char* enode, size_t len);
+
+/// Removes a reserved peer from a running client, as the `parity_removeReservedPeer` RPC does. The
+/// peer stays connected as a regular peer if the peer limits allow it.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `enode` isn't a valid enode URL, and a
+/// `ParityError` code on error.
+int parity_remove_reserved_peer(void* parity, const char* enode, size_t len);
+
+/// Retrieves the ports the client listens on for peers, which the system picked if the configured
+/// port is 0, as with `--port 0`.
+///
+/// `out_port` will receive the TCP port peers connect to, and `out_udp_port`, which may be NULL,
+/// the UDP port of the peer discovery. Unless set with `--udp-port`, both are the same.
+///
+/// Returns 0 on success, `PARITY_ERROR_NETWORK_DISABLED` if the client has no network or it is
+/// paused with `parity_network_pause`, and a `ParityError` code on any other error.
+int parity_p2p_port(void* parity, uint16_t* out_port, uint16_t* out_udp_port);
+
+/// Retrieves the address the HTTP JSON-RPC server is bound to, such as `127.0.0.1:8545`. This is
+/// the way to find out which port was picked if the configuration asked for port 0.
+///
+/// `out_ptr` will receive a pointer to the null-terminated string and `out_len` its length, not
+/// counting the null terminator. The string must be freed with `parity_string_destroy`.
+///
+/// Returns 0 on success, `PARITY_ERROR_HTTP_SERVER_DISABLED` if the client runs without the HTTP
+/// JSON-RPC server, and a `ParityError` code on any other error.
+int parity_jsonrpc_http_address(void* parity, char** out_ptr, size_t* out_len);
+
+/// Retrieves the version string of Parity, such as
+/// `Parity-Ethereum/v2.3.0-unstable-1a2b3c4-20181210/x86_64-linux-gnu/rustc1.31.0`.
+///
+/// `out_ptr` will receive a pointer to the null-terminated string and `out_len` its length, not
+/// counting the null terminator.
+///
+/// The string is static: it stays valid for as long as the library is loaded and must not be
+/// freed.
+void parity_version(const char** out_ptr, size_t* out_len);
+
+/// Computes the Keccak-256 hash of `len` bytes at `input`, as used by Ethereum for transaction
+/// and block hashes, with the same implementation as the client. `input` may be NULL if `len` is
+/// 0.
+///
+/// `out_hash` must point to a buffer of 32 bytes, which receives the hash.
+void parity_keccak256(const uint8_t* input, size_t len, uint8_t* out_hash);
+
+/// Retrieves the message of the last error that happened on the calling thread.
+///
+/// `out_ptr` will receive a pointer to the null-terminated message and `out_len` its length, not
+/// counting the null terminator. If no error happened yet, an empty string is returned.
+///
+/// The message is kept until another error happens on the same thread, at which point the pointer
+/// becomes invalid. It is owned by Parity and must not be freed.
+///
+/// Filled by every function returning a `ParityError` code when it fails with another code than
+/// `PARITY_ERROR_PANIC`, and by `parity_rpc_begin` when it returns NULL. The errors passed to the
+/// callbacks of the asynchronous functions aren't recorded, as they happen on other threads.
+void parity_last_error_message(const char** out_ptr, size_t* out_len);
+
+/// Destroys a string that was allocated by Parity.
+///
+/// Functions that hand out a string owned by Parity document that it must be released through
+/// this function. Never pass these strings to `free()`, as they might not have been allocated
+/// with the same allocator.
+///
+/// **Warning**: Must not be called on a string you allocated yourself, nor twice on the same
+///				string.
+void parity_string_destroy(char* s);
+
+/// Sets a callback to call for each log record emitted by Parity.
+///
+/// Must be called before `parity_start` in order to capture the logs of the startup as well. The
+/// `--logging` CLI option has no effect once this logger is installed.
+///
+/// - `filter` and `filter_len` contain the log levels to enable, with the same syntax as the
+///   `--logging` CLI option (for example `sync=debug,rpc=trace`). Can be an empty string.
+/// - The first parameter of the callback is the value of `custom`. The second one is the level of
+///   the record: 1 for error, 2 for warning, 3 for info, 4 for debug and 5 for trace. The last four
+///   parameters are the string pointers and lengths of the target (the module that emitted the
+///   record) and of the message.
+/// - Returns 0 on success, and a `ParityError` code on error. The logger can only be installed
+///   once per process, and not at all if another Rust library already installed one.
+///
+/// ## Thread safety
+///
+/// The callback can be called from any thread and multiple times simultaneously. Make sure that
+/// your code is thread safe.
+///
+/// **Important**: Keep in mind that the strings passed to the callback are not null-terminated.
+///
+int parity_set_logger(void (*callback)(void* custom, int level, const char* target, size_t target_len, const char* msg, size_t msg_len), void* custom, const char* filter, size_t filter_len);
+
 /// Sets a callback to call when a panic happens in the Rust code.
 ///
 /// The callback takes as parameter the custom param (the one passed to this function), plus the
@@ -110,14 +1599,83 @@ int parity_rpc(void* parity, const char* rpc, size_t len, char* out_str, size_t*
 ///
 /// Note that this method sets the panic hook for the whole program, and not just for Parity. In
 /// other words, if you use multiple Rust libraries at once (and not just Parity), then a panic
-/// in any Rust code will call this callback as well.
+/// in any Rust code will call this callback as well. Calling this function again replaces the
+/// previous callback, and `parity_clear_panic_hook` restores the hook that was in place before.
 ///
 /// ## Thread safety
 ///
 /// The callback can be called from any thread and multiple times simultaneously. Make sure that
 /// your code is thread safe.
 ///
-int parity_set_panic_hook(void (*cb)(void* param, const char* msg, size_t msg_len), void* param);
+void parity_set_panic_hook(void (*cb)(void* param, const char* msg, size_t msg_len), void* param);
+
+/// Selects whether a panic in the Rust code aborts the process or is recovered from.
+///
+/// - `mode` is a `ParityPanicMode`.
+///
+/// By default, the functions catch the panics happening while they run and return
+/// `PARITY_ERROR_PANIC`, and the panics of the background threads of the clients only stop these
+/// threads. In the abort mode, the panic hook of the library writes the panic message and its
+/// backtrace to the standard error and aborts the process right after calling the callback of
+/// `parity_set_panic_hook`, wherever the panic happened. This helps finding bugs during
+/// development. The panic hook is installed if it isn't already, and `parity_clear_panic_hook`
+/// goes back to the recover mode.
+///
+/// Returns 0 on success, `PARITY_ERROR_INVALID_VALUE` if `mode` is unknown, and a `ParityError`
+/// code on error.
+int parity_set_panic_mode(int mode);
+
+/// Restores the panic hook of the program that was in place before the library installed its own,
+/// with `parity_set_panic_hook` or `parity_set_panic_mode`.
+/// Call it before unloading the library, as the hook would otherwise keep pointing into it.
+///
+/// Panics no longer call the callback of `parity_set_panic_hook` until one of these functions is
+/// called again, and are recovered from as with `PARITY_PANIC_MODE_RECOVER`. Does nothing if the hook of the library isn't installed.
+void parity_clear_panic_hook(void);
+
+/// Sets a callback to call once when the client begins shutting down on its own, which happens
+/// after a database failure, right after the callback of `parity_set_db_error_callback`. The
+/// client isn't usable afterwards and should be destroyed, so that the application doesn't call
+/// into a dead client.
+///
+/// The callback is called with `custom` as only parameter, from the thread where the shutdown
+/// begins. Destroying the client with `parity_destroy`, `parity_destroy_with_timeout` or
+/// `parity_shutdown_async`, or replacing it with `parity_restart` or `parity_revert_to_block`,
+/// doesn't call it.
+///
+/// Calling this function again replaces the callback, and passing NULL removes it.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_set_shutdown_callback(void* parity, void (*callback)(void* custom), void* custom);
+
+/// Sets a callback to call when the JSON-RPC servers of the client, HTTP, WebSockets and IPC, are
+/// bound and accept connections, so that startup scripts know when to send their first requests.
+///
+/// The callback is called as soon as the servers are bound. They are bound before `parity_start`
+/// returns, so the callback is first called right away, from the calling thread and before this
+/// function returns. It is called again, from the thread calling `parity_restart` or
+/// `parity_revert_to_block`, once the servers of the restarted client are bound.
+///
+/// Calling this function again replaces the callback, and passing NULL removes it.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_set_rpc_ready_callback(void* parity, void (*callback)(void* custom), void* custom);
+
+/// Sets a callback to call once when the client fails to write to its database, for example
+/// because of a disk error, which it can't recover from. The client then begins shutting down and
+/// calls the callback of `parity_set_shutdown_callback`; the application can show `msg` to the
+/// user, and maybe remove the database to resynchronize from scratch.
+///
+/// The callback is called from the thread that hit the failure, before it panics, with `custom`
+/// and the error message as parameters. Light clients never call it. Destroying the client doesn't
+/// call it.
+///
+/// **Important**: Keep in mind that the message is not null-terminated.
+///
+/// Calling this function again replaces the callback, and passing NULL removes it.
+///
+/// Returns 0 on success, and a `ParityError` code on error.
+int parity_set_db_error_callback(void* parity, void (*callback)(void* custom, const char* msg, size_t msg_len), void* custom);
 
 #ifdef __cplusplus
 }
@@ -17,21 +17,59 @@
 //! Note that all the structs and functions here are documented in `parity.h`, to avoid
 //! duplicating documentation.
 
+extern crate env_logger;
+extern crate ethereum_types;
+extern crate futures;
 #[cfg(feature = "jni")]
 extern crate jni;
+extern crate keccak_hash;
+#[macro_use]
+extern crate lazy_static;
+extern crate log;
 extern crate parity_ethereum;
+extern crate parity_runtime;
+extern crate parity_version;
 extern crate panic_hook;
+extern crate serde_json;
+extern crate tempdir;
 
+use std::cell::RefCell;
+use std::cmp;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ffi::CString;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
 use std::os::raw::{c_char, c_void, c_int};
 use std::panic;
+use std::path::Path;
+use std::process;
 use std::ptr;
 use std::slice;
 use std::str;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use env_logger::filter::{Builder as FilterBuilder, Filter};
+use ethereum_types::{Address, H256, U256};
+use futures::{future, Async, Future, Stream};
+use futures::executor::{self, Notify, Spawn};
+use log::LevelFilter;
+use futures::sync::mpsc as futures_mpsc;
+use futures::sync::oneshot;
+use parity_ethereum::{ArgsError, BlockImport, CallOutcome, ClientMode, KeyFileError, LocalizedLogEntry, LogListener, NewBlockListener, PubSubSession, RpcApi, RunningClient, SignMessageError, TransactionOutcome, TransactionStatus, UnlockError};
+use parity_runtime::{Executor, Interval, Runtime};
+use tempdir::TempDir;
 
 #[cfg(feature = "jni")]
 use std::mem;
 #[cfg(feature = "jni")]
-use jni::{JNIEnv, objects::JClass, objects::JString, sys::jlong, sys::jobjectArray};
+use jni::{JNIEnv, JavaVM, objects::GlobalRef, objects::JClass, objects::JObject, objects::JString, objects::JValue, sys::jlong, sys::jobjectArray};
+
+#[cfg(feature = "napi")]
+mod napi;
 
 #[repr(C)]
 pub struct ParityParams {
@@ -40,6 +78,175 @@ pub struct ParityParams {
 	pub on_client_restart_cb_custom: *mut c_void,
 }
 
+#[repr(C)]
+pub struct ParityPeerInfo {
+	pub enode: *const c_char,
+	pub enode_len: usize,
+	pub client_version: *const c_char,
+	pub client_version_len: usize,
+	pub protocol_version: u32,
+	pub head_hash: [u8; 32],
+}
+
+#[repr(C)]
+pub struct ParityLog {
+	pub address: [u8; 20],
+	pub topics: *const u8,
+	pub topics_count: usize,
+	pub data: *const u8,
+	pub data_len: usize,
+	pub block_number: u64,
+	pub block_hash: [u8; 32],
+	pub transaction_hash: [u8; 32],
+	pub transaction_index: usize,
+	pub log_index: usize,
+	pub removed: c_int,
+}
+
+#[repr(C)]
+pub struct ParityBlock {
+	pub number: u64,
+	pub hash: [u8; 32],
+	pub parent_hash: [u8; 32],
+	pub timestamp: u64,
+	pub gas_used: u64,
+	pub gas_limit: u64,
+	pub transaction_count: usize,
+	pub transaction_hashes: *const u8,
+}
+
+#[repr(C)]
+pub struct ParityReceipt {
+	pub status: c_int,
+	pub gas_used: u64,
+	pub cumulative_gas_used: u64,
+	pub has_contract_address: c_int,
+	pub contract_address: [u8; 20],
+	pub block_number: u64,
+	pub block_hash: [u8; 32],
+	pub transaction_index: usize,
+	pub logs: *const ParityLog,
+	pub logs_count: usize,
+}
+
+// The values are part of the public API and must never be renumbered.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParityError {
+	Ok = 0,
+	InvalidUtf8 = 1,
+	CliParse = 2,
+	StartFailed = 3,
+	NullCallback = 4,
+	Timeout = 5,
+	BufferTooSmall = 6,
+	RpcFailed = 7,
+	Panic = 8,
+	ConfigRead = 9,
+	ConfigParse = 10,
+	LoggerAlreadySet = 11,
+	Cancelled = 12,
+	InvalidValue = 13,
+	NetworkDisabled = 14,
+	DatabaseLocked = 15,
+	HttpServerDisabled = 16,
+	NullPointer = 17,
+	Unsupported = 18,
+	Unavailable = 19,
+	SealFailed = 20,
+	AccountsUnavailable = 21,
+	FlushFailed = 22,
+	SnapshotFailed = 23,
+	PermissionDenied = 24,
+	CallReverted = 25,
+	CallFailed = 26,
+	WrongHandleType = 27,
+	InvalidPassword = 28,
+	UnknownAccount = 29,
+	AccountLocked = 30,
+	RevertFailed = 31,
+}
+
+// Bits of the status returned by `parity_node_health`. They are part of the public API.
+const NODE_HEALTH_SYNCING: u32 = 1 << 0;
+const NODE_HEALTH_PEERS_OK: u32 = 1 << 1;
+const NODE_HEALTH_NO_PEERS: u32 = 1 << 2;
+const NODE_HEALTH_PEERS_CHECK_DISABLED: u32 = 1 << 3;
+
+// Outcomes of `parity_import_block`. They are part of the public API.
+const BLOCK_IMPORT_QUEUED: c_int = 0;
+const BLOCK_IMPORT_ALREADY_IN_CHAIN: c_int = 1;
+const BLOCK_IMPORT_REJECTED: c_int = 2;
+
+// Kinds of clients returned by `parity_client_mode`. They are part of the public API.
+const CLIENT_MODE_ARCHIVE: c_int = 0;
+const CLIENT_MODE_FULL: c_int = 1;
+const CLIENT_MODE_LIGHT: c_int = 2;
+
+// Policies of `parity_set_subscription_buffer` once the buffer is full. They are part of the
+// public API.
+const SUBSCRIPTION_BUFFER_BLOCK: c_int = 0;
+const SUBSCRIPTION_BUFFER_DROP_OLDEST: c_int = 1;
+const SUBSCRIPTION_BUFFER_DROP_NEWEST: c_int = 2;
+
+// Statuses of the receipts passed by `parity_get_receipt`. They are part of the public API.
+const RECEIPT_STATUS_FAILED: c_int = 0;
+const RECEIPT_STATUS_SUCCEEDED: c_int = 1;
+const RECEIPT_STATUS_UNKNOWN: c_int = 2;
+
+// States of the transactions returned by `parity_transaction_status`. They are part of the public
+// API.
+const TRANSACTION_STATUS_UNKNOWN: c_int = 0;
+const TRANSACTION_STATUS_PENDING: c_int = 1;
+const TRANSACTION_STATUS_QUEUED: c_int = 2;
+const TRANSACTION_STATUS_MINED: c_int = 3;
+const TRANSACTION_STATUS_DROPPED: c_int = 4;
+
+// Modes of `parity_set_panic_mode`. They are part of the public API.
+const PANIC_MODE_RECOVER: c_int = 0;
+const PANIC_MODE_ABORT: c_int = 1;
+
+// Default timeout of RPC queries, in milliseconds.
+const QUERY_TIMEOUT_MS: usize = 5 * 60 * 1000;
+
+// Interval at which `parity_wait_for_sync` checks the health of the node, in milliseconds.
+const WAIT_FOR_SYNC_POLL_MS: u64 = 100;
+
+// Default maximum number of notifications buffered for a subscription.
+const SUBSCRIPTION_BUFFER: usize = 16;
+
+// Messages of the `PARITY_ERROR_UNAVAILABLE` errors shared by several functions.
+const PRUNED_STATE_ERROR: &str = "The state of the best block was pruned";
+const NO_GAS_PRICES_ERROR: &str = "No gas price of the recent blocks is known";
+
+// Number of threads of the runtimes created for the RPC queries, or 0 for the tokio default.
+static RPC_THREAD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// Whether the panic hook of the library aborts the process, as set with `parity_set_panic_mode`.
+static ABORT_ON_PANIC: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+	static ref VERSION: CString = CString::new(parity_version::version())
+		.expect("version string never contains a null byte; qed");
+
+	// Addresses of the handles returned by `into_handle` and not taken back yet.
+	static ref LIVE_HANDLES: Mutex<HashSet<usize>> = Mutex::new(HashSet::new());
+
+	// Addresses of the clients returned by `parity_start` and not destroyed yet.
+	static ref LIVE_CLIENTS: Mutex<HashSet<usize>> = Mutex::new(HashSet::new());
+
+	// Panic hook that was in place before the library installed its own, restored by
+	// `parity_clear_panic_hook`. `None` while the hook of the library isn't installed.
+	static ref PREVIOUS_PANIC_HOOK: Mutex<Option<Arc<PanicHook>>> = Mutex::new(None);
+}
+
+type PanicHook = Box<Fn(&panic::PanicInfo) + Sync + Send + 'static>;
+
+thread_local! {
+	// Message of the last error that happened on this thread, for `parity_last_error_message`.
+	static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
 #[no_mangle]
 pub unsafe extern fn parity_config_from_cli(args: *const *const c_char, args_lens: *const usize, len: usize, output: *mut *mut c_void) -> c_int {
 	panic::catch_unwind(|| {
@@ -56,212 +263,4087 @@ pub unsafe extern fn parity_config_from_cli(args: *const *const c_char, args_len
 				let string = slice::from_raw_parts(arg as *const u8, len);
 				match String::from_utf8(string.to_owned()) {
 					Ok(a) => args.push(a),
-					Err(_) => return 1,
+					Err(err) => {
+						set_last_error(err);
+						return ParityError::InvalidUtf8 as c_int;
+					},
 				};
 			}
 
 			args
 		};
 
-		match parity_ethereum::Configuration::parse_cli(&args) {
-			Ok(mut cfg) => {
-				// Always disable the auto-updater when used as a library.
-				cfg.args.arg_auto_update = "none".to_owned();
+		config_from_args(&args, output)
+	}).unwrap_or(ParityError::Panic as c_int)
+}
 
-				let cfg = Box::into_raw(Box::new(cfg));
-				*output = cfg as *mut _;
-				0
+#[no_mangle]
+pub unsafe extern fn parity_config_from_file(path: *const c_char, path_len: usize, output: *mut *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		*output = ptr::null_mut();
+
+		let path = {
+			let string = slice::from_raw_parts(path as *const u8, path_len);
+			match str::from_utf8(string) {
+				Ok(p) => p,
+				Err(err) => {
+					set_last_error(err);
+					return ParityError::InvalidUtf8 as c_int;
+				},
+			}
+		};
+
+		// Load the file the same way the `--config` CLI flag does.
+		config_from_args(&["parity", "--config", path], output)
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_clone(cfg: *const c_void, output: *mut *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		*output = ptr::null_mut();
+		let cfg: &parity_ethereum::Configuration = match handle_ref(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		*output = into_handle(cfg.clone());
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_chain(cfg: *mut c_void, chain: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		match config_str(chain, len) {
+			Ok(chain) => {
+				cfg.args.arg_chain = chain;
+				cfg.chain_spec_json = None;
+				ParityError::Ok as c_int
 			},
-			Err(_) => {
-				1
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_chain_spec_json(cfg: *mut c_void, json: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		match config_str(json, len) {
+			Ok(json) => {
+				cfg.chain_spec_json = Some(json);
+				ParityError::Ok as c_int
 			},
+			Err(err) => err as c_int,
 		}
-	}).unwrap_or(1)
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
 #[no_mangle]
-pub unsafe extern fn parity_config_destroy(cfg: *mut c_void) {
-	let _ = panic::catch_unwind(|| {
-		let _cfg = Box::from_raw(cfg as *mut parity_ethereum::Configuration);
-	});
+pub unsafe extern fn parity_config_set_datadir(cfg: *mut c_void, path: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		match config_str(path, len) {
+			Ok(path) => {
+				cfg.args.arg_base_path = Some(path);
+				ParityError::Ok as c_int
+			},
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
 #[no_mangle]
-pub unsafe extern fn parity_start(cfg: *const ParityParams, output: *mut *mut c_void) -> c_int {
+pub unsafe extern fn parity_config_set_log_file(cfg: *mut c_void, path: *const c_char, len: usize) -> c_int {
 	panic::catch_unwind(|| {
-		*output = ptr::null_mut();
-		let cfg: &ParityParams = &*cfg;
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		let path = match config_str(path, len) {
+			Ok(path) => path,
+			Err(err) => return err as c_int,
+		};
 
-		let config = Box::from_raw(cfg.configuration as *mut parity_ethereum::Configuration);
+		// Catches the paths that can't be written now rather than when the client starts. The file
+		// itself is only created by the logger, once the client starts.
+		if let Err(err) = check_log_file(Path::new(&path)) {
+			set_last_error(format!("Cannot write to log file {}: {}", path, err));
+			return ParityError::InvalidValue as c_int;
+		}
 
-		let on_client_restart_cb = {
-			let cb = CallbackStr(cfg.on_client_restart_cb, cfg.on_client_restart_cb_custom);
-			move |new_chain: String| { cb.call(&new_chain); }
-		};
+		cfg.args.arg_log_file = Some(path);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
 
-		let action = match parity_ethereum::start(*config, on_client_restart_cb, || {}) {
-			Ok(action) => action,
-			Err(_) => return 1,
+#[no_mangle]
+pub unsafe extern fn parity_config_set_bootnodes(cfg: *mut c_void, enodes: *const *const c_char, lens: *const usize, count: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
 		};
 
-		match action {
-			parity_ethereum::ExecutionAction::Instant(Some(s)) => { println!("{}", s); 0 },
-			parity_ethereum::ExecutionAction::Instant(None) => 0,
-			parity_ethereum::ExecutionAction::Running(client) => {
-				*output = Box::into_raw(Box::<parity_ethereum::RunningClient>::new(client)) as *mut c_void;
-				0
+		let mut list = Vec::with_capacity(count);
+		if count != 0 {
+			let enode_ptrs = slice::from_raw_parts(enodes, count);
+			let enode_lens = slice::from_raw_parts(lens, count);
+			for (&enode, &len) in enode_ptrs.iter().zip(enode_lens.iter()) {
+				match config_str(enode, len) {
+					Ok(enode) => list.push(enode),
+					Err(err) => return err as c_int,
+				}
 			}
 		}
-	}).unwrap_or(1)
+
+		// An empty list replaces the bootnodes of the chain with none.
+		let bootnodes = Some(list.join(","));
+		if let Err(err) = parity_ethereum::to_bootnodes(&bootnodes) {
+			set_last_error(err);
+			return ParityError::InvalidValue as c_int;
+		}
+		cfg.args.arg_bootnodes = bootnodes;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
 #[no_mangle]
-pub unsafe extern fn parity_destroy(client: *mut c_void) {
-	let _ = panic::catch_unwind(|| {
-		let client = Box::from_raw(client as *mut parity_ethereum::RunningClient);
-		client.shutdown();
-	});
+pub unsafe extern fn parity_config_set_nat(cfg: *mut c_void, nat: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+
+		let nat = match config_str(nat, len) {
+			Ok(nat) => nat,
+			Err(err) => return err as c_int,
+		};
+		if let Err(err) = parity_ethereum::validate_nat(&nat) {
+			set_last_error(err);
+			return ParityError::InvalidValue as c_int;
+		}
+		cfg.args.arg_nat = nat;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
 #[no_mangle]
-pub unsafe extern fn parity_rpc(client: *mut c_void, query: *const c_char, len: usize, out_str: *mut c_char, out_len: *mut usize) -> c_int {
+pub unsafe extern fn parity_config_set_reserved_peers(cfg: *mut c_void, enodes: *const *const c_char, lens: *const usize, count: usize) -> c_int {
 	panic::catch_unwind(|| {
-		let client: &mut parity_ethereum::RunningClient = &mut *(client as *mut parity_ethereum::RunningClient);
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
 
-		let query_str = {
-			let string = slice::from_raw_parts(query as *const u8, len);
-			match str::from_utf8(string) {
-				Ok(a) => a,
-				Err(_) => return 1,
+		let mut list = Vec::with_capacity(count);
+		if count != 0 {
+			let enode_ptrs = slice::from_raw_parts(enodes, count);
+			let enode_lens = slice::from_raw_parts(lens, count);
+			for (&enode, &len) in enode_ptrs.iter().zip(enode_lens.iter()) {
+				let enode = match config_str(enode, len) {
+					Ok(enode) => enode,
+					Err(err) => return err as c_int,
+				};
+				if let Err(err) = parity_ethereum::validate_reserved_peer(&enode) {
+					set_last_error(err);
+					return ParityError::InvalidValue as c_int;
+				}
+				list.push(enode);
 			}
+		}
+
+		cfg.reserved_peers = Some(list);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_jsonrpc_port(cfg: *mut c_void, port: u16) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		cfg.args.arg_jsonrpc_port = port;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_min_peers(cfg: *mut c_void, min_peers: u16) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		if cfg.args.arg_max_peers.map_or(false, |max_peers| min_peers > max_peers) {
+			set_last_error(ArgsError::PeerConfiguration);
+			return ParityError::InvalidValue as c_int;
+		}
+
+		cfg.args.arg_min_peers = Some(min_peers);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_jsonrpc_cors(cfg: *mut c_void, origins: *const *const c_char, lens: *const usize, count: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		match config_list(origins, lens, count) {
+			Ok(origins) => {
+				// The deprecated `--rpccorsdomain` would take precedence.
+				cfg.args.arg_rpccorsdomain = None;
+				cfg.args.arg_jsonrpc_cors = origins;
+				ParityError::Ok as c_int
+			},
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_jsonrpc_hosts(cfg: *mut c_void, hosts: *const *const c_char, lens: *const usize, count: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		match config_list(hosts, lens, count) {
+			Ok(hosts) => {
+				cfg.args.arg_jsonrpc_hosts = hosts;
+				ParityError::Ok as c_int
+			},
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_jsonrpc_apis(cfg: *mut c_void, apis: *const *const c_char, lens: *const usize, count: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
 		};
+		if count == 0 {
+			set_last_error("At least one API must be enabled, use `--no-jsonrpc` to disable the server");
+			return ParityError::InvalidValue as c_int;
+		}
 
-		if let Some(output) = client.rpc_query_sync(query_str) {
-			let q_out_len = output.as_bytes().len();
-			if *out_len < q_out_len {
-				return 1;
+		let api_ptrs = slice::from_raw_parts(apis, count);
+		let api_lens = slice::from_raw_parts(lens, count);
+
+		let mut list = Vec::with_capacity(count);
+		for (&api, &len) in api_ptrs.iter().zip(api_lens.iter()) {
+			let api = match config_str(api, len) {
+				Ok(api) => api,
+				Err(err) => return err as c_int,
+			};
+			// Same syntax as `--jsonrpc-apis`: the "all" and "safe" sets, and APIs prefixed with
+			// "-" removed from the ones listed before.
+			let valid = match api.as_str() {
+				"all" | "safe" => true,
+				name if name.starts_with('-') => name[1..].parse::<RpcApi>().is_ok(),
+				name => name.parse::<RpcApi>().is_ok(),
+			};
+			if !valid {
+				set_last_error(format!("Unknown JSON-RPC API {:?}", api));
+				return ParityError::InvalidValue as c_int;
 			}
+			list.push(api);
+		}
 
-			ptr::copy_nonoverlapping(output.as_bytes().as_ptr(), out_str as *mut u8, q_out_len);
-			*out_len = q_out_len;
-			0
-		} else {
-			1
+		// The deprecated `--rpcapi` would take precedence.
+		cfg.args.arg_rpcapi = None;
+		cfg.args.arg_jsonrpc_apis = list.join(",");
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_set_jsonrpc_threads(cfg: *mut c_void, threads: u32) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		if threads == 0 {
+			set_last_error("The number of JSON-RPC threads must be greater than 0");
+			return ParityError::InvalidValue as c_int;
 		}
-	}).unwrap_or(1)
+
+		cfg.args.arg_jsonrpc_threads = threads as usize;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
 #[no_mangle]
-pub unsafe extern fn parity_set_panic_hook(callback: extern "C" fn(*mut c_void, *const c_char, usize), param: *mut c_void) {
-	let cb = CallbackStr(Some(callback), param);
-	panic_hook::set_with(move |panic_msg| {
-		cb.call(panic_msg);
+pub unsafe extern fn parity_config_set_in_memory(cfg: *mut c_void, enable: c_int) -> c_int {
+	panic::catch_unwind(|| {
+		let cfg: &mut parity_ethereum::Configuration = match handle_mut(cfg) {
+			Ok(cfg) => cfg,
+			Err(err) => return err as c_int,
+		};
+		cfg.in_memory = enable != 0;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_config_destroy(cfg: *mut c_void) {
+	if cfg.is_null() {
+		return;
+	}
+
+	let _ = panic::catch_unwind(|| {
+		let _cfg = take_handle::<parity_ethereum::Configuration>(cfg);
 	});
 }
 
-// Internal structure for handling callbacks that get passed a string.
-struct CallbackStr(Option<extern "C" fn(*mut c_void, *const c_char, usize)>, *mut c_void);
-unsafe impl Send for CallbackStr {}
-unsafe impl Sync for CallbackStr {}
-impl CallbackStr {
-	fn call(&self, new_chain: &str) {
-		if let Some(ref cb) = self.0 {
-			cb(self.1, new_chain.as_bytes().as_ptr() as *const _, new_chain.len())
+#[no_mangle]
+pub unsafe extern fn parity_start(cfg: *const ParityParams, output: *mut *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		*output = ptr::null_mut();
+		if cfg.is_null() || (*cfg).configuration.is_null() {
+			return null_pointer() as c_int;
 		}
-	}
+		let cfg: &ParityParams = &*cfg;
+
+		let config = match take_handle(cfg.configuration) {
+			Ok(config) => config,
+			Err(err) => return err as c_int,
+		};
+		let on_client_restart = CallbackStr(cfg.on_client_restart_cb, cfg.on_client_restart_cb_custom);
+
+		match start_client(config, on_client_restart) {
+			Ok(Some(client)) => {
+				*output = client;
+				ParityError::Ok as c_int
+			},
+			Ok(None) => ParityError::Ok as c_int,
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
-#[cfg(feature = "jni")]
 #[no_mangle]
-pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_configFromCli(env: JNIEnv, _: JClass, cli: jobjectArray) -> jlong {
-	let cli_len = env.get_array_length(cli).expect("invalid Java bindings");
+pub unsafe extern fn parity_restart(client: *mut *mut c_void, new_chain: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		if client.is_null() || (*client).is_null() {
+			return null_pointer() as c_int;
+		}
+
+		let new_chain = match config_str(new_chain, len) {
+			Ok(new_chain) => new_chain,
+			Err(err) => return err as c_int,
+		};
+
+		if !unregister_client(*client) {
+			set_last_error("The client was destroyed or wasn't returned by `parity_start`");
+			return ParityError::NullPointer as c_int;
+		}
+		let old_client: ParityClient = take_handle(*client).expect("registered clients are client handles; qed");
+		*client = ptr::null_mut();
+
+		let mut config = old_client.config.clone();
+		config.args.flag_testnet = false;
+		config.args.arg_chain = new_chain.clone();
+		config.chain_spec_json = None;
+		let on_client_restart = old_client.on_client_restart;
+		let rpc_timeout_ms = old_client.rpc_timeout_ms.load(Ordering::SeqCst);
+		let rpc_ready = old_client.rpc_ready.lock().expect("rpc ready callback lock poisoned").take();
 
-	let mut jni_strings = Vec::with_capacity(cli_len as usize);
-	let mut opts = Vec::with_capacity(cli_len as usize);
-	let mut opts_lens = Vec::with_capacity(cli_len as usize);
+		// The databases of both chains may be the same, so the old client must be gone first.
+		old_client.shutdown();
+		on_client_restart.call(&new_chain);
 
-	for n in 0 .. cli_len {
-		let elem = env.get_object_array_element(cli, n).expect("invalid Java bindings");
-		let elem_str: JString = elem.into();
-		match env.get_string(elem_str) {
-			Ok(s) => {
-				opts.push(s.as_ptr());
-				opts_lens.push(s.to_bytes().len());
-				jni_strings.push(s);
+		match start_replacement_client(config, on_client_restart, rpc_timeout_ms, rpc_ready) {
+			Ok(new_client) => {
+				*client = new_client;
+				ParityError::Ok as c_int
 			},
-			Err(err) => {
-				let _ = env.throw_new("java/lang/Exception", err.to_string());
-				return 0
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_revert_to_block(client: *mut *mut c_void, block_number: u64) -> c_int {
+	panic::catch_unwind(|| {
+		if client.is_null() || (*client).is_null() {
+			return null_pointer() as c_int;
+		}
+
+		{
+			let client: &ParityClient = match handle_ref(*client) {
+				Ok(client) => client,
+				Err(err) => return err as c_int,
+			};
+
+			// Only revert chains whose state is kept, while the client can still be used.
+			if client.client.mode() != ClientMode::Archive || client.config.in_memory {
+				set_last_error("Reverting the chain requires a full node with `--pruning archive` and its database on disk");
+				return ParityError::Unsupported as c_int;
 			}
-		};
-	}
+			let (best_block, _) = client.client.best_block();
+			if block_number >= best_block {
+				set_last_error(format!("Block #{} isn't older than the best block #{}", block_number, best_block));
+				return ParityError::InvalidValue as c_int;
+			}
+		}
 
-	let mut out = ptr::null_mut();
-	match parity_config_from_cli(opts.as_ptr(), opts_lens.as_ptr(), cli_len as usize, &mut out) {
-		0 => out as usize as jlong,
-		_ => {
-			let _ = env.throw_new("java/lang/Exception", "failed to create config object");
-			0
-		},
-	}
+		if !unregister_client(*client) {
+			set_last_error("The client was destroyed or wasn't returned by `parity_start`");
+			return ParityError::NullPointer as c_int;
+		}
+		let old_client: ParityClient = take_handle(*client).expect("registered clients are client handles; qed");
+		*client = ptr::null_mut();
+
+		let config = old_client.config.clone();
+		let on_client_restart = old_client.on_client_restart;
+		let rpc_timeout_ms = old_client.rpc_timeout_ms.load(Ordering::SeqCst);
+		let rpc_ready = old_client.rpc_ready.lock().expect("rpc ready callback lock poisoned").take();
+
+		// The database can't be shared, so the chain is reverted while no client runs, and the
+		// client is started again even if that failed.
+		old_client.shutdown();
+		let reverted = parity_ethereum::revert_chain(config.clone(), block_number);
+
+		match start_replacement_client(config, on_client_restart, rpc_timeout_ms, rpc_ready) {
+			Ok(new_client) => {
+				*client = new_client;
+				match reverted {
+					Ok(()) => ParityError::Ok as c_int,
+					Err(err) => {
+						set_last_error(err);
+						ParityError::RevertFailed as c_int
+					},
+				}
+			},
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
-#[cfg(feature = "jni")]
 #[no_mangle]
-pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_build(env: JNIEnv, _: JClass, config: jlong) -> jlong {
-	let params = ParityParams {
-		configuration: config as usize as *mut c_void,
-		.. mem::zeroed()
-	};
+pub unsafe extern fn parity_destroy(client: *mut c_void) {
+	// Also covers null pointers and clients that were already destroyed.
+	if !unregister_client(client) {
+		return;
+	}
 
-	let mut out = ptr::null_mut();
-	match parity_start(&params, &mut out) {
-		0 => out as usize as jlong,
-		_ => {
-			let _ = env.throw_new("java/lang/Exception", "failed to start Parity");
-			0
-		},
+	let _ = panic::catch_unwind(|| {
+		let client: ParityClient = take_handle(client).expect("registered clients are client handles; qed");
+		client.shutdown();
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_destroy_with_timeout(client: *mut c_void, timeout_ms: usize, out_clean: *mut c_int) -> c_int {
+	if out_clean.is_null() {
+		set_last_error("`out_clean` is NULL");
+		return null_pointer() as c_int;
 	}
+	*out_clean = 0;
+	if let Err(err) = check_handle::<ParityClient>(client) {
+		return err as c_int;
+	}
+	// Only one of two threads destroying the same client at the same time gets it.
+	if !unregister_client(client) {
+		set_last_error("The client was already destroyed");
+		return ParityError::WrongHandleType as c_int;
+	}
+
+	panic::catch_unwind(|| {
+		let client = SendClient(take_handle(client).expect("registered clients are client handles; qed"));
+
+		let (tx, rx) = mpsc::channel();
+		thread::Builder::new()
+			.name("parity-shutdown".into())
+			.spawn(move || {
+				let SendClient(client) = client;
+				client.shutdown();
+				let _ = tx.send(());
+			})
+			.expect("parity-shutdown thread shouldn't fail; qed");
+
+		// On timeout, the shutdown keeps going in the background.
+		let clean = rx.recv_timeout(Duration::from_millis(timeout_ms as u64)).is_ok();
+		*out_clean = clean as c_int;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
 }
 
-#[cfg(feature = "jni")]
 #[no_mangle]
-pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_destroy(_env: JNIEnv, _: JClass, parity: jlong) {
-	let parity = parity as usize as *mut c_void;
-	parity_destroy(parity);
+pub unsafe extern fn parity_shutdown_async(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void)>, custom: *mut c_void) {
+	let callback = callback.map(|callback| CallbackShutdown(callback, custom));
+	if !unregister_client(client) {
+		if let Some(callback) = callback {
+			callback.call();
+		}
+		return;
+	}
+
+	let _ = panic::catch_unwind(|| {
+		let client = SendClient(take_handle(client).expect("registered clients are client handles; qed"));
+		thread::Builder::new()
+			.name("parity-shutdown".into())
+			.spawn(move || {
+				let SendClient(client) = client;
+				client.shutdown();
+				if let Some(callback) = callback {
+					callback.call();
+				}
+			})
+			.expect("parity-shutdown thread shouldn't fail; qed");
+	});
 }
 
-#[cfg(feature = "jni")]
 #[no_mangle]
-pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_rpcQueryNative<'a>(env: JNIEnv<'a>, _: JClass, parity: jlong, rpc: JString) -> JString<'a> {
-	let parity = parity as usize as *mut c_void;
+pub extern fn parity_client_is_valid(client: *const c_void) -> c_int {
+	panic::catch_unwind(|| {
+		LIVE_CLIENTS.lock().expect("live clients lock poisoned").contains(&(client as usize)) as c_int
+	}).unwrap_or(0)
+}
 
-	let rpc = match env.get_string(rpc) {
-		Ok(s) => s,
-		Err(err) => {
-			let _ = env.throw_new("java/lang/Exception", err.to_string());
-			return env.new_string("").expect("Creating an empty string never fails");
-		},
-	};
+#[no_mangle]
+pub unsafe extern fn parity_rpc(client: *mut c_void, query: *const c_char, len: usize, out_str: *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
 
-	let mut out_len = 255;
-	let mut out = [0u8; 256];
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
 
-	match parity_rpc(parity, rpc.as_ptr(), rpc.to_bytes().len(), out.as_mut_ptr() as *mut c_char, &mut out_len) {
-		0 => (),
-		_ => {
-			let _ = env.throw_new("java/lang/Exception", "failed to perform RPC query");
-			return env.new_string("").expect("Creating an empty string never fails");
-		},
-	}
+		match client.rpc_query(query_str, client.rpc_timeout()) {
+			Ok(output) => {
+				let q_out_len = output.as_bytes().len();
+				if *out_len < q_out_len {
+					set_last_error(format!("The response takes {} bytes, but the buffer only holds {}", q_out_len, *out_len));
+					return ParityError::BufferTooSmall as c_int;
+				}
 
-	let out = str::from_utf8(&out[..out_len])
-		.expect("parity always generates an UTF-8 RPC response");
-	match env.new_string(out) {
-		Ok(s) => s,
-		Err(err) => {
-			let _ = env.throw_new("java/lang/Exception", err.to_string());
-			return env.new_string("").expect("Creating an empty string never fails");
+				ptr::copy_nonoverlapping(output.as_bytes().as_ptr(), out_str as *mut u8, q_out_len);
+				*out_len = q_out_len;
+				ParityError::Ok as c_int
+			},
+			Err(err) => err as c_int,
 		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_sync(client: *mut c_void, query: *const c_char, len: usize, timeout_ms: usize, out_buf: *mut c_char, out_buf_len: usize, out_written: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		let timeout = match timeout_ms {
+			0 => client.rpc_timeout(),
+			ms => Duration::from_millis(ms as u64),
+		};
+
+		let response = match client.rpc_query(query_str, timeout) {
+			Ok(response) => response,
+			Err(err) => return err as c_int,
+		};
+
+		*out_written = response.len();
+		if out_buf_len < response.len() {
+			set_last_error(format!("The response takes {} bytes, but the buffer only holds {}", response.len(), out_buf_len));
+			return ParityError::BufferTooSmall as c_int;
+		}
+
+		ptr::copy_nonoverlapping(response.as_bytes().as_ptr(), out_buf as *mut u8, response.len());
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_cancelable(client: *mut c_void, query: *const c_char, len: usize, callback: Option<extern "C" fn(*mut c_void, *const c_char, usize)>, userdata: *mut c_void, out_token: *mut *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		*out_token = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		if callback.is_none() {
+			return null_callback() as c_int;
+		}
+		let callback = Arc::new(CallbackStr(callback, userdata));
+		let on_timeout = callback.clone();
+
+		// The query is dropped as soon as `parity_rpc_abort` sends the signal. A sender dropped
+		// without sending anything doesn't abort the query.
+		let (abort_tx, abort_rx) = oneshot::channel();
+		let aborted = abort_rx.or_else(|_| future::empty()).map_err(|_| ());
+		let future = client.client.rpc_query(query_str, None);
+
+		client.executor().spawn_with_timeout(
+			move || future
+				.map(move |response| match response {
+					Some(response) => callback.call(&response),
+					None => callback.call_null(),
+				})
+				.select(aborted)
+				.then(|_| Ok(())),
+			client.rpc_timeout(),
+			move || on_timeout.call_null(),
+		);
+
+		*out_token = Box::into_raw(Box::new(abort_tx)) as *mut c_void;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_abort(token: *mut c_void) {
+	if token.is_null() {
+		return;
+	}
+
+	let _ = panic::catch_unwind(|| {
+		let abort_tx = Box::from_raw(token as *mut oneshot::Sender<()>);
+		// Fails if the query already completed, in which case there is nothing to abort.
+		let _ = abort_tx.send(());
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_begin(client: *mut c_void, query: *const c_char, len: usize) -> *mut c_void {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(_) => return ptr::null_mut(),
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(_) => return ptr::null_mut(),
+		};
+
+		let future: PolledFuture = Box::new(client.client.rpc_query(query_str, None));
+		let query = PolledQuery {
+			future: executor::spawn(future),
+			notify: Arc::new(NoopNotify),
+			deadline: Instant::now() + client.rpc_timeout(),
+			response: None,
+		};
+		into_handle(query)
+	}).unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_poll(handle: *mut c_void, out_ptr: *mut *const c_char, out_len: *mut usize, out_done: *mut c_int) -> c_int {
+	panic::catch_unwind(|| {
+		*out_ptr = ptr::null();
+		*out_len = 0;
+		*out_done = 0;
+		let query: &mut PolledQuery = match handle_mut(handle) {
+			Ok(query) => query,
+			Err(err) => return err as c_int,
+		};
+
+		// The outcome is kept in the handle, so that polling a completed query again is harmless.
+		if query.response.is_none() {
+			match query.future.poll_future_notify(&query.notify, 0) {
+				Ok(Async::Ready(Some(response))) => query.response = Some(Ok(response)),
+				Ok(Async::Ready(None)) | Err(()) => {
+					set_last_error("The request failed");
+					query.response = Some(Err(ParityError::RpcFailed));
+				},
+				Ok(Async::NotReady) if Instant::now() >= query.deadline => {
+					set_last_error("The request timed out");
+					query.response = Some(Err(ParityError::Timeout));
+				},
+				Ok(Async::NotReady) => return ParityError::Ok as c_int,
+			}
+		}
+
+		*out_done = 1;
+		match *query.response.as_ref().expect("the outcome was set above; qed") {
+			Ok(ref response) => {
+				*out_ptr = response.as_ptr() as *const c_char;
+				*out_len = response.len();
+				ParityError::Ok as c_int
+			},
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_end(handle: *mut c_void) {
+	if handle.is_null() {
+		return;
+	}
+
+	let _ = panic::catch_unwind(|| {
+		let _ = take_handle::<PolledQuery>(handle);
+	});
+}
+
+#[cfg(feature = "swift")]
+#[no_mangle]
+pub unsafe extern fn parity_rpc_with_context(client: *mut c_void, query: *const c_char, len: usize, callback: Option<extern "C" fn(*mut c_void, *const c_char, usize)>, context: *mut c_void, release_context: Option<extern "C" fn(*mut c_void)>) -> c_int {
+	panic::catch_unwind(|| {
+		// Created first so that the context is released on every path, including the failures.
+		let callback = Arc::new(CallbackContext { callback, context, release_context });
+
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		if callback.callback.is_none() {
+			return null_callback() as c_int;
+		}
+
+		let future = client.client.rpc_query(query_str, None);
+		let on_timeout = callback.clone();
+		client.executor().spawn_with_timeout(
+			move || future.map(move |response| callback.call(response.as_ref().map(|r| r.as_str()))),
+			client.rpc_timeout(),
+			move || on_timeout.call(None),
+		);
+
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_batch(client: *mut c_void, queries: *const *const c_char, query_lens: *const usize, count: usize, callback: Option<extern "C" fn(*mut c_void, usize, *const c_char, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackBatch(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		let (query_ptrs, query_lens) = if count == 0 {
+			(&[][..], &[][..])
+		} else {
+			(slice::from_raw_parts(queries, count), slice::from_raw_parts(query_lens, count))
+		};
+
+		let mut futures = Vec::with_capacity(count);
+		for (&query, &len) in query_ptrs.iter().zip(query_lens.iter()) {
+			match parse_json_query(query, len) {
+				Ok(query) => futures.push(client.client.rpc_query(query, None)),
+				Err(err) => return err as c_int,
+			}
+		}
+
+		// All the queries of the batch are driven concurrently by the shared runtime.
+		let timeout = client.rpc_timeout();
+		let executor = client.executor();
+		let callback = Arc::new(callback);
+		for (index, future) in futures.into_iter().enumerate() {
+			let callback = callback.clone();
+			let on_timeout = callback.clone();
+			executor.spawn_with_timeout(
+				move || future.map(move |response| callback.call(index, response.as_ref().map(|r| r.as_str()))),
+				timeout,
+				move || on_timeout.call(index, None),
+			);
+		}
+
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_raw(client: *mut c_void, query: *const c_char, len: usize, callback: Option<extern "C" fn(*mut c_void, c_int, *const u8, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackBytes(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		client.spawn_query(query_str, client.rpc_timeout(), move |response| {
+			callback.call(response.as_ref().map(|r| r.as_bytes()).map_err(|err| *err))
+		});
+
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_checked(client: *mut c_void, query: *const c_char, len: usize, callback: Option<extern "C" fn(*mut c_void, c_int, c_int, *const c_char, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackRpc(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		client.spawn_query(query_str, client.rpc_timeout(), move |response| {
+			callback.call(response.as_ref().map(|r| r.as_str()).map_err(|err| *err))
+		});
+
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_rpc_tagged(client: *mut c_void, query: *const c_char, len: usize, tag: u64, callback: Option<extern "C" fn(*mut c_void, u64, c_int, *const c_char, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackTagged(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		client.spawn_query(query_str, client.rpc_timeout(), move |response| {
+			callback.call(tag, response.as_ref().map(|r| r.as_str()).map_err(|err| *err))
+		});
+
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_rpc_timeout(client: *mut c_void, timeout_ms: usize) {
+	let _ = panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(_) => return,
+		};
+		client.rpc_timeout_ms.store(timeout_ms, Ordering::SeqCst);
+	});
+}
+
+#[no_mangle]
+pub extern fn parity_set_rpc_thread_count(thread_count: usize) {
+	RPC_THREAD_COUNT.store(thread_count, Ordering::SeqCst);
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_send_raw_transaction(client: *mut c_void, rlp: *const u8, len: usize, callback: Option<extern "C" fn(*mut c_void, *const u8, *const c_char, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackTransaction(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		if rlp.is_null() && len != 0 {
+			return null_pointer() as c_int;
+		}
+		let rlp = if len == 0 { &[][..] } else { slice::from_raw_parts(rlp, len) };
+		match client.client.send_raw_transaction(rlp) {
+			Ok(hash) => callback.call(Ok(&hash)),
+			Err(err) => callback.call(Err(&err)),
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_list_accounts(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void, *const u8)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		match client.client.accounts() {
+			Ok(accounts) => {
+				for address in accounts {
+					callback(userdata, address.as_ptr());
+				}
+				ParityError::Ok as c_int
+			},
+			Err(err) => {
+				set_last_error(err);
+				ParityError::AccountsUnavailable as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_author(client: *mut c_void, address: *const u8) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		miner_result(client.client.set_author(address))
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_unlock_account(client: *mut c_void, address: *const u8, password: *const c_char, password_len: usize, duration_secs: u64) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let password = match account_password(password, password_len) {
+			Ok(password) => password,
+			Err(err) => return err as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		match client.client.unlock_account(address, password, Duration::from_secs(duration_secs)) {
+			Ok(()) => ParityError::Ok as c_int,
+			Err(UnlockError::InvalidPassword) => {
+				set_last_error("The password doesn't match the account");
+				ParityError::InvalidPassword as c_int
+			},
+			Err(UnlockError::UnknownAccount) => {
+				set_last_error("The client doesn't manage the account");
+				ParityError::UnknownAccount as c_int
+			},
+			Err(UnlockError::Failed(err)) => {
+				set_last_error(err);
+				ParityError::AccountsUnavailable as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_import_private_key(client: *mut c_void, secret: *const u8, password: *const c_char, password_len: usize, out_address: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if secret.is_null() || out_address.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let password = match account_password(password, password_len) {
+			Ok(password) => password,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.import_private_key(slice::from_raw_parts(secret, 32), password) {
+			Ok(address) => {
+				ptr::copy_nonoverlapping(address.as_ptr(), out_address, 20);
+				ParityError::Ok as c_int
+			},
+			Err(err) => {
+				let code = if err.starts_with(parity_ethereum::INVALID_PRIVATE_KEY_ERROR) {
+					ParityError::InvalidValue
+				} else {
+					ParityError::AccountsUnavailable
+				};
+				set_last_error(err);
+				code as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_export_account(client: *mut c_void, address: *const u8, password: *const c_char, password_len: usize, out_ptr: *mut *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() {
+			return null_pointer() as c_int;
+		}
+		*out_ptr = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let password = match account_password(password, password_len) {
+			Ok(password) => password,
+			Err(err) => return err as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		match client.client.export_account(address, password) {
+			Ok(key_file) => {
+				*out_len = key_file.len();
+				*out_ptr = CString::new(key_file).expect("JSON strings never contain a null byte; qed").into_raw();
+				ParityError::Ok as c_int
+			},
+			Err(err) => key_file_error(err) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_import_keystore(client: *mut c_void, json: *const c_char, len: usize, password: *const c_char, password_len: usize, out_address: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if json.is_null() || out_address.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let password = match account_password(password, password_len) {
+			Ok(password) => password,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.import_key_file(slice::from_raw_parts(json as *const u8, len), password) {
+			Ok(address) => {
+				ptr::copy_nonoverlapping(address.as_ptr(), out_address, 20);
+				ParityError::Ok as c_int
+			},
+			Err(err) => key_file_error(err) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+// Returns the code of an error exporting or importing a key file, and stores its message.
+fn key_file_error(err: KeyFileError) -> ParityError {
+	match err {
+		KeyFileError::InvalidPassword => {
+			set_last_error("The password doesn't match the account");
+			ParityError::InvalidPassword
+		},
+		KeyFileError::UnknownAccount => {
+			set_last_error("The client doesn't manage the account");
+			ParityError::UnknownAccount
+		},
+		KeyFileError::InvalidKeyFile(err) => {
+			set_last_error(err);
+			ParityError::InvalidValue
+		},
+		KeyFileError::Failed(err) => {
+			set_last_error(err);
+			ParityError::AccountsUnavailable
+		},
+	}
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_sign(client: *mut c_void, address: *const u8, message: *const u8, message_len: usize, out_signature: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() || out_signature.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		let message = if message_len == 0 { &[][..] } else { slice::from_raw_parts(message, message_len) };
+		match client.client.sign_message(address, message) {
+			Ok(signature) => {
+				ptr::copy_nonoverlapping(signature.as_ptr(), out_signature, 65);
+				ParityError::Ok as c_int
+			},
+			Err(SignMessageError::Locked) => {
+				set_last_error("The account is locked, unlock it with `parity_unlock_account` first");
+				ParityError::AccountLocked as c_int
+			},
+			Err(SignMessageError::UnknownAccount) => {
+				set_last_error("The client doesn't manage the account");
+				ParityError::UnknownAccount as c_int
+			},
+			Err(SignMessageError::Failed(err)) => {
+				set_last_error(err);
+				ParityError::AccountsUnavailable as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_min_gas_price(client: *mut c_void, wei: *const u8, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		if wei.is_null() && len != 0 {
+			return null_pointer() as c_int;
+		}
+		if len > 32 {
+			set_last_error("The gas price doesn't fit in 256 bits");
+			return ParityError::InvalidValue as c_int;
+		}
+
+		let gas_price = if len == 0 { U256::zero() } else { U256::from_little_endian(slice::from_raw_parts(wei, len)) };
+		miner_result(client.client.set_min_gas_price(gas_price))
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_dev_mine_block(client: *mut c_void, out_block_number: *mut u64) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.force_seal_block() {
+			Ok(number) => {
+				*out_block_number = number;
+				ParityError::Ok as c_int
+			},
+			Err(err) => client_error(err, ParityError::SealFailed) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_dev_mine_blocks(client: *mut c_void, count: u64, out_final_block: *mut u64) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		if count == 0 {
+			set_last_error("The number of blocks to seal must not be 0");
+			return ParityError::InvalidValue as c_int;
+		}
+
+		// Each block is imported before the next one is sealed on top of it.
+		for _ in 0..count {
+			match client.client.force_seal_block() {
+				Ok(number) => *out_final_block = number,
+				Err(err) => return client_error(err, ParityError::SealFailed) as c_int,
+			}
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_import_block(client: *mut c_void, rlp: *const u8, len: usize, out_result: *mut c_int) -> c_int {
+	panic::catch_unwind(|| {
+		if (rlp.is_null() && len != 0) || out_result.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let rlp = if len == 0 { &[][..] } else { slice::from_raw_parts(rlp, len) };
+		*out_result = match client.client.import_block(rlp) {
+			BlockImport::Queued => BLOCK_IMPORT_QUEUED,
+			BlockImport::AlreadyInChain => BLOCK_IMPORT_ALREADY_IN_CHAIN,
+			BlockImport::Rejected(reason) => {
+				set_last_error(reason);
+				BLOCK_IMPORT_REJECTED
+			},
+		};
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_subscribe(client: *mut c_void, query: *const c_char, len: usize, callback: Option<extern "C" fn(*mut c_void, *const c_char, usize)>, userdata: *mut c_void, out_sub_id: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let query_str = match parse_json_query(query, len) {
+			Ok(q) => q,
+			Err(err) => return err as c_int,
+		};
+
+		if callback.is_none() {
+			return null_callback() as c_int;
+		}
+		let cb = CallbackStr(callback, userdata);
+
+		match client.subscribe(query_str, move |msg| cb.call(msg)) {
+			Ok(sub_id) => {
+				*out_sub_id = sub_id;
+				ParityError::Ok as c_int
+			},
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_unsubscribe(client: *mut c_void, sub_id: usize) {
+	let _ = panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(_) => return,
+		};
+		client.remove_subscription(sub_id);
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_subscription_buffer(client: *mut c_void, sub_id: usize, max: usize, policy: c_int, on_dropped: Option<extern "C" fn(*mut c_void, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let policy = match policy {
+			SUBSCRIPTION_BUFFER_BLOCK => BufferPolicy::Block,
+			SUBSCRIPTION_BUFFER_DROP_OLDEST => BufferPolicy::DropOldest,
+			SUBSCRIPTION_BUFFER_DROP_NEWEST => BufferPolicy::DropNewest,
+			_ => {
+				set_last_error(format!("Unknown subscription buffer policy: {}", policy));
+				return ParityError::InvalidValue as c_int;
+			},
+		};
+		if max == 0 {
+			set_last_error("The subscription buffer must hold at least one notification");
+			return ParityError::InvalidValue as c_int;
+		}
+		let on_dropped = on_dropped.map(|on_dropped| CallbackDropped(on_dropped, userdata));
+
+		match client.set_subscription_buffer(sub_id, max, policy, on_dropped) {
+			Ok(()) => ParityError::Ok as c_int,
+			Err(err) => err as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_on_new_block(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void, u64, *const u8)>, userdata: *mut c_void, out_id: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackBlock(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		let listener = client.client.add_new_block_listener(move |number, hash| {
+			callback.call(number, &hash);
+		});
+		*out_id = client.add_new_block_listener(listener);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_remove_new_block_listener(client: *mut c_void, id: usize) {
+	let _ = panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(_) => return,
+		};
+		client.remove_new_block_listener(id);
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_subscribe_logs(client: *mut c_void, filter: *const c_char, len: usize, callback: Option<extern "C" fn(*mut c_void, *const ParityLog)>, userdata: *mut c_void, out_sub_id: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let filter = match parse_json_query(filter, len) {
+			Ok(filter) => filter,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackLog(callback, userdata),
+			None => return null_callback() as c_int,
+		};
+
+		let listener = client.client.add_log_listener(filter, move |log, removed| {
+			let topics = log_topics(log);
+			callback.call(&parity_log(log, &topics, removed));
+		});
+		match listener {
+			Ok(listener) => {
+				*out_sub_id = client.add_log_listener(listener);
+				ParityError::Ok as c_int
+			},
+			Err(err) => client_error(err, ParityError::InvalidValue) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_unsubscribe_logs(client: *mut c_void, sub_id: usize) {
+	let _ = panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(_) => return,
+		};
+		client.remove_log_listener(sub_id);
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_sync_status(client: *mut c_void, out_current_block: *mut u64, out_highest_block: *mut u64, out_is_syncing: *mut c_int) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		// Without a network, nothing tells the highest block or whether the client is syncing.
+		if client.network_paused.load(Ordering::SeqCst) || client.client.p2p_ports().is_none() {
+			set_last_error("The network of the client isn't running, so it doesn't sync");
+			return ParityError::Unavailable as c_int;
+		}
+		let status = client.client.sync_status();
+
+		*out_current_block = status.current_block;
+		*out_highest_block = status.highest_block;
+		*out_is_syncing = if status.is_syncing { 1 } else { 0 };
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_sync_progress_callback(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void, u64, u64, f64)>, userdata: *mut c_void, interval_ms: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => CallbackProgress(callback, userdata),
+			None => {
+				client.set_sync_progress_reporter(None);
+				return ParityError::Ok as c_int;
+			},
+		};
+
+		if interval_ms == 0 {
+			set_last_error("The interval of the sync progress reports must not be 0");
+			return ParityError::InvalidValue as c_int;
+		}
+		let interval = Duration::from_millis(interval_ms as u64);
+
+		let reader = client.client.sync_status_reader();
+		let mut last = (Instant::now(), client.client.sync_status().current_block);
+
+		// Ends when the client is shut down.
+		let reporter = Interval::new(last.0 + interval, interval)
+			.map_err(|_| ())
+			.for_each(move |now| {
+				let status = reader.sync_status().ok_or(())?;
+
+				let elapsed = now - last.0;
+				let elapsed = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1_000_000_000.0;
+				let imported = status.current_block.saturating_sub(last.1);
+				last = (now, status.current_block);
+
+				callback.call(status.current_block, status.highest_block, imported as f64 / elapsed);
+				Ok(())
+			});
+
+		// The reporter also ends once the sender is dropped, by a new call or by shutdown.
+		let (stop_tx, stop_rx) = oneshot::channel::<()>();
+		client.executor().spawn(reporter.select(stop_rx.then(|_| Ok(()))).then(|_| Ok(())));
+		client.set_sync_progress_reporter(Some(stop_tx));
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_wait_for_sync(client: *mut c_void, timeout_ms: usize, out_synced: *mut c_int) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+		loop {
+			if client.client.health().is_healthy() {
+				*out_synced = 1;
+				return ParityError::Ok as c_int;
+			}
+
+			let now = Instant::now();
+			if now >= deadline {
+				*out_synced = 0;
+				return ParityError::Ok as c_int;
+			}
+			thread::sleep(cmp::min(deadline - now, Duration::from_millis(WAIT_FOR_SYNC_POLL_MS)));
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_metrics_prometheus(client: *mut c_void, out_ptr: *mut *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		*out_ptr = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let metrics = prometheus_metrics(&client.client);
+		*out_len = metrics.len();
+		*out_ptr = CString::new(metrics).expect("the metrics never contain a null byte; qed").into_raw();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_node_health(client: *mut c_void, out_status: *mut u32) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		let health = client.client.health();
+
+		let mut status = 0;
+		if health.is_syncing {
+			status |= NODE_HEALTH_SYNCING;
+		}
+		if health.num_peers > 0 {
+			status |= NODE_HEALTH_PEERS_OK;
+		} else {
+			status |= NODE_HEALTH_NO_PEERS;
+		}
+		if health.peers_check_disabled {
+			status |= NODE_HEALTH_PEERS_CHECK_DISABLED;
+		}
+
+		*out_status = status;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_peer_count(client: *mut c_void, out_count: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		*out_count = client.client.peer_count();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_network_pause(client: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		if !client.network_paused.swap(true, Ordering::SeqCst) {
+			client.client.stop_network();
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_network_resume(client: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		// Starting a running network would register its protocols a second time.
+		if client.network_paused.swap(false, Ordering::SeqCst) {
+			client.client.start_network();
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_peers(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void, *const ParityPeerInfo)>, custom: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		for peer in client.client.peers() {
+			let enode = peer.enode.as_ref().map_or("", |enode| enode.as_str());
+			let (protocol_version, head_hash) = peer.protocol.unwrap_or_default();
+			let info = ParityPeerInfo {
+				enode: enode.as_ptr() as *const _,
+				enode_len: enode.len(),
+				client_version: peer.client_version.as_ptr() as *const _,
+				client_version_len: peer.client_version.len(),
+				protocol_version,
+				head_hash: head_hash.0,
+			};
+			callback(custom, &info);
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_peer_limits(client: *mut c_void, max_in: u32, max_out: u32) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		client.client.set_peer_limits(max_in, max_out);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_client_mode(client: *mut c_void, out_mode: *mut c_int) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		*out_mode = match client.client.mode() {
+			ClientMode::Archive => CLIENT_MODE_ARCHIVE,
+			ClientMode::Full => CLIENT_MODE_FULL,
+			ClientMode::Light => CLIENT_MODE_LIGHT,
+		};
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_chain_id(client: *mut c_void, out_chain_id: *mut u64) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.chain_id() {
+			Some(chain_id) => {
+				*out_chain_id = chain_id;
+				ParityError::Ok as c_int
+			},
+			None => {
+				set_last_error("Replay protection isn't active at the best block yet");
+				ParityError::Unavailable as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_best_block(client: *mut c_void, out_number: *mut u64, out_hash: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if out_hash.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let (number, hash) = client.client.best_block();
+		*out_number = number;
+		ptr::copy_nonoverlapping(hash.as_ptr(), out_hash, 32);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_network_id(client: *mut c_void, out_network_id: *mut u64) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		*out_network_id = client.client.network_id();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_active_fork(client: *mut c_void, out_ptr: *mut *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		*out_ptr = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let fork = client.client.active_fork();
+		*out_len = fork.len();
+		*out_ptr = CString::new(fork).expect("fork names never contain a null byte; qed").into_raw();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_database_size(client: *mut c_void, out_bytes: *mut u64) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.database_size() {
+			Ok(size) => {
+				*out_bytes = size;
+				ParityError::Ok as c_int
+			},
+			Err(err) => {
+				set_last_error(err);
+				ParityError::Unavailable as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_flush_database(client: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.flush_database() {
+			Ok(()) => ParityError::Ok as c_int,
+			Err(err) => {
+				set_last_error(err);
+				ParityError::FlushFailed as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_get_balance(client: *mut c_void, address: *const u8, out_wei: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() || out_wei.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		match client.client.balance(&address) {
+			Ok(balance) => u256_result(balance, out_wei, PRUNED_STATE_ERROR),
+			Err(err) => client_error(err, ParityError::Unavailable) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_get_storage_at(client: *mut c_void, address: *const u8, key: *const u8, out_value: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() || key.is_null() || out_value.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		let key = H256::from_slice(slice::from_raw_parts(key, 32));
+		match client.client.storage_at(&address, &key) {
+			Ok(Some(value)) => {
+				ptr::copy_nonoverlapping(value.as_ptr(), out_value, 32);
+				ParityError::Ok as c_int
+			},
+			Ok(None) => {
+				set_last_error(PRUNED_STATE_ERROR);
+				ParityError::Unavailable as c_int
+			},
+			Err(err) => client_error(err, ParityError::Unavailable) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_eth_call(client: *mut c_void, to: *const u8, data: *const u8, data_len: usize, callback: Option<extern "C" fn(*mut c_void, c_int, *const u8, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		if to.is_null() || (data.is_null() && data_len != 0) {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		let to = Address::from_slice(slice::from_raw_parts(to, 20));
+		let data = if data_len == 0 { Vec::new() } else { slice::from_raw_parts(data, data_len).to_vec() };
+		match client.client.call(to, data) {
+			CallOutcome::Success(output) => {
+				callback(userdata, ParityError::Ok as c_int, output.as_ptr(), output.len());
+				ParityError::Ok as c_int
+			},
+			CallOutcome::Reverted(output) => {
+				callback(userdata, ParityError::CallReverted as c_int, output.as_ptr(), output.len());
+				ParityError::Ok as c_int
+			},
+			CallOutcome::Failed(err) => client_error(err, ParityError::CallFailed) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_estimate_gas(client: *mut c_void, from: *const u8, to: *const u8, data: *const u8, data_len: usize, value: *const u8, value_len: usize, out_gas: *mut u64) -> c_int {
+	panic::catch_unwind(|| {
+		if from.is_null() || (data.is_null() && data_len != 0) || (value.is_null() && value_len != 0) {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		if value_len > 32 {
+			set_last_error("The value doesn't fit in 256 bits");
+			return ParityError::InvalidValue as c_int;
+		}
+
+		let from = Address::from_slice(slice::from_raw_parts(from, 20));
+		let to = if to.is_null() { None } else { Some(Address::from_slice(slice::from_raw_parts(to, 20))) };
+		let data = if data_len == 0 { Vec::new() } else { slice::from_raw_parts(data, data_len).to_vec() };
+		let value = if value_len == 0 { U256::zero() } else { U256::from_little_endian(slice::from_raw_parts(value, value_len)) };
+		match client.client.estimate_gas(from, to, data, value) {
+			// The estimate is capped at ten times the gas limit of the best block.
+			Ok(gas) if gas > U256::from(u64::max_value()) => {
+				set_last_error(format!("The gas estimate {} doesn't fit in 64 bits", gas));
+				ParityError::CallFailed as c_int
+			},
+			Ok(gas) => {
+				*out_gas = gas.low_u64();
+				ParityError::Ok as c_int
+			},
+			Err(err) => client_error(err, ParityError::CallFailed) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_trace_transaction(client: *mut c_void, hash: *const u8, callback: Option<extern "C" fn(*mut c_void, *const u8, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		if hash.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		let hash = H256::from_slice(slice::from_raw_parts(hash, 32));
+		match client.client.replay_transaction_traces(hash) {
+			Ok(Some(traces)) => {
+				callback(userdata, traces.as_ptr(), traces.len());
+				ParityError::Ok as c_int
+			},
+			Ok(None) => {
+				set_last_error("The transaction is unknown or the state it was executed on was pruned");
+				ParityError::Unavailable as c_int
+			},
+			Err(err) => client_error(err, ParityError::CallFailed) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_get_block(client: *mut c_void, number: u64, include_txs: c_int, callback: Option<extern "C" fn(*mut c_void, *const ParityBlock)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		let block = match client.client.block_summary(number, include_txs != 0) {
+			Ok(Some(block)) => block,
+			Ok(None) => {
+				set_last_error(format!("There is no block #{} in the canonical chain", number));
+				return ParityError::Unavailable as c_int;
+			},
+			Err(err) => return client_error(err, ParityError::Unavailable) as c_int,
+		};
+
+		let (gas_used, gas_limit) = match (gas_u64(block.gas_used, "gas used"), gas_u64(block.gas_limit, "gas limit")) {
+			(Ok(gas_used), Ok(gas_limit)) => (gas_used, gas_limit),
+			(Err(err), _) | (_, Err(err)) => return err as c_int,
+		};
+		let transaction_hashes: Option<Vec<u8>> = block.transaction_hashes.map(|hashes| {
+			hashes.iter().flat_map(|hash| hash.iter().cloned()).collect()
+		});
+		let block = ParityBlock {
+			number,
+			hash: block.hash.0,
+			parent_hash: block.parent_hash.0,
+			timestamp: block.timestamp,
+			gas_used,
+			gas_limit,
+			transaction_count: block.transaction_count,
+			transaction_hashes: transaction_hashes.as_ref().map_or(ptr::null(), |hashes| hashes.as_ptr()),
+		};
+		callback(userdata, &block);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_get_receipt(client: *mut c_void, hash: *const u8, callback: Option<extern "C" fn(*mut c_void, *const ParityReceipt)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		if hash.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		let hash = H256::from_slice(slice::from_raw_parts(hash, 32));
+		let receipt = match client.client.transaction_receipt(hash) {
+			Ok(Some(receipt)) => receipt,
+			Ok(None) => {
+				callback(userdata, ptr::null());
+				return ParityError::Ok as c_int;
+			},
+			Err(err) => return client_error(err, ParityError::Unavailable) as c_int,
+		};
+
+		let (gas_used, cumulative_gas_used) = match (gas_u64(receipt.gas_used, "gas used"), gas_u64(receipt.cumulative_gas_used, "cumulative gas used")) {
+			(Ok(gas_used), Ok(cumulative_gas_used)) => (gas_used, cumulative_gas_used),
+			(Err(err), _) | (_, Err(err)) => return err as c_int,
+		};
+		let topics: Vec<Vec<u8>> = receipt.logs.iter().map(log_topics).collect();
+		let logs: Vec<ParityLog> = receipt.logs.iter()
+			.zip(&topics)
+			.map(|(log, topics)| parity_log(log, topics, false))
+			.collect();
+		let receipt = ParityReceipt {
+			status: match receipt.outcome {
+				TransactionOutcome::StatusCode(0) => RECEIPT_STATUS_FAILED,
+				TransactionOutcome::StatusCode(_) => RECEIPT_STATUS_SUCCEEDED,
+				TransactionOutcome::StateRoot(_) | TransactionOutcome::Unknown => RECEIPT_STATUS_UNKNOWN,
+			},
+			gas_used,
+			cumulative_gas_used,
+			has_contract_address: receipt.contract_address.is_some() as c_int,
+			contract_address: receipt.contract_address.unwrap_or_default().0,
+			block_number: receipt.block_number,
+			block_hash: receipt.block_hash.0,
+			transaction_index: receipt.transaction_index,
+			logs: logs.as_ptr(),
+			logs_count: logs.len(),
+		};
+		callback(userdata, &receipt);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+// Converts an amount of gas passed to a callback as 64 bits, which only chains with absurd gas
+// limits exceed.
+fn gas_u64(gas: U256, name: &str) -> Result<u64, ParityError> {
+	if gas > U256::from(u64::max_value()) {
+		set_last_error(format!("The {} {} doesn't fit in 64 bits", name, gas));
+		return Err(ParityError::InvalidValue);
+	}
+	Ok(gas.low_u64())
+}
+
+// Concatenates the topics of a log, as `ParityLog` passes them.
+fn log_topics(log: &LocalizedLogEntry) -> Vec<u8> {
+	log.topics.iter().flat_map(|topic| topic.iter().cloned()).collect()
+}
+
+// Builds the C representation of a log, pointing to the topics returned by `log_topics`.
+fn parity_log(log: &LocalizedLogEntry, topics: &[u8], removed: bool) -> ParityLog {
+	ParityLog {
+		address: log.address.0,
+		topics: topics.as_ptr(),
+		topics_count: log.topics.len(),
+		data: log.data.as_ptr(),
+		data_len: log.data.len(),
+		block_number: log.block_number,
+		block_hash: log.block_hash.0,
+		transaction_hash: log.transaction_hash.0,
+		transaction_index: log.transaction_index,
+		log_index: log.log_index,
+		removed: removed as c_int,
+	}
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_get_proof(client: *mut c_void, address: *const u8, storage_keys: *const u8, key_count: usize, callback: Option<extern "C" fn(*mut c_void, *const u8, usize)>, userdata: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		if address.is_null() || (storage_keys.is_null() && key_count != 0) {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		let address = Address::from_slice(slice::from_raw_parts(address, 20));
+		let storage_keys: Vec<H256> = if key_count == 0 {
+			Vec::new()
+		} else {
+			slice::from_raw_parts(storage_keys, key_count * 32).chunks(32).map(H256::from_slice).collect()
+		};
+		match client.client.prove_account_storage(address, &storage_keys) {
+			Ok(Some(proof)) => {
+				callback(userdata, proof.as_ptr(), proof.len());
+				ParityError::Ok as c_int
+			},
+			Ok(None) => {
+				set_last_error(PRUNED_STATE_ERROR);
+				ParityError::Unavailable as c_int
+			},
+			Err(err) => client_error(err, ParityError::Unavailable) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_take_snapshot(client: *mut c_void, out_path: *mut *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		*out_path = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let path = match client.client.take_snapshot() {
+			Ok(path) => path.to_string_lossy().into_owned(),
+			Err(err) => return client_error(err, ParityError::SnapshotFailed) as c_int,
+		};
+
+		*out_len = path.len();
+		*out_path = CString::new(path).expect("paths never contain a null byte; qed").into_raw();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_restore_snapshot(client: *mut c_void, path: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let path = match config_str(path, len) {
+			Ok(path) => path,
+			Err(err) => return err as c_int,
+		};
+
+		match client.client.restore_snapshot(Path::new(&path)) {
+			Ok(()) => ParityError::Ok as c_int,
+			Err(err) => client_error(err, ParityError::SnapshotFailed) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_gas_price_median(client: *mut c_void, out_wei: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if out_wei.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+		u256_result(client.client.gas_price_median(), out_wei, NO_GAS_PRICES_ERROR)
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_gas_price_percentile(client: *mut c_void, percentile: usize, out_wei: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if out_wei.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		if percentile > 100 {
+			set_last_error(format!("The percentile must be between 0 and 100, got {}", percentile));
+			return ParityError::InvalidValue as c_int;
+		}
+		u256_result(client.client.gas_price_percentile(percentile), out_wei, NO_GAS_PRICES_ERROR)
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_suggested_fees(client: *mut c_void, out_base_fee: *mut u8, out_tip: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if out_base_fee.is_null() || out_tip.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		// The chains don't have a base fee, so the whole gas price goes to the miner.
+		let result = u256_result(client.client.suggested_gas_price(), out_base_fee, NO_GAS_PRICES_ERROR);
+		if result == ParityError::Ok as c_int {
+			U256::zero().to_big_endian(slice::from_raw_parts_mut(out_tip, 32));
+		}
+		result
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_transaction_status(client: *mut c_void, hash: *const u8, out_status: *mut c_int) -> c_int {
+	panic::catch_unwind(|| {
+		if hash.is_null() || out_status.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let hash = H256::from_slice(slice::from_raw_parts(hash, 32));
+		match client.client.transaction_status(hash) {
+			Ok(status) => {
+				*out_status = match status {
+					TransactionStatus::Unknown => TRANSACTION_STATUS_UNKNOWN,
+					TransactionStatus::Pending => TRANSACTION_STATUS_PENDING,
+					TransactionStatus::Queued => TRANSACTION_STATUS_QUEUED,
+					TransactionStatus::Mined => TRANSACTION_STATUS_MINED,
+					TransactionStatus::Dropped => TRANSACTION_STATUS_DROPPED,
+				};
+				ParityError::Ok as c_int
+			},
+			Err(err) => client_error(err, ParityError::Unavailable) as c_int,
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_txqueue_status(client: *mut c_void, out_pending: *mut usize, out_queued: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let (pending, queued) = client.client.transaction_queue_status();
+		*out_pending = pending;
+		*out_queued = queued;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_enode(client: *mut c_void, out_ptr: *mut *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		*out_ptr = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let enode = match client.client.enode() {
+			Some(enode) => enode,
+			None => {
+				set_last_error("The network is disabled");
+				return ParityError::NetworkDisabled as c_int;
+			},
+		};
+
+		*out_len = enode.len();
+		*out_ptr = CString::new(enode).expect("enode URLs never contain a null byte; qed").into_raw();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_node_public_key(client: *mut c_void, out_public_key: *mut u8) -> c_int {
+	panic::catch_unwind(|| {
+		if out_public_key.is_null() {
+			return null_pointer() as c_int;
+		}
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let public_key = match client.client.node_public_key() {
+			Some(public_key) => public_key,
+			None => {
+				set_last_error("The network is disabled");
+				return ParityError::NetworkDisabled as c_int;
+			},
+		};
+
+		slice::from_raw_parts_mut(out_public_key, 64).copy_from_slice(&public_key);
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_add_reserved_peer(client: *mut c_void, enode: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let enode = match config_str(enode, len) {
+			Ok(enode) => enode,
+			Err(err) => return err as c_int,
+		};
+		match client.client.add_reserved_peer(enode) {
+			Ok(()) => ParityError::Ok as c_int,
+			Err(err) => {
+				set_last_error(err);
+				ParityError::InvalidValue as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_remove_reserved_peer(client: *mut c_void, enode: *const c_char, len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let enode = match config_str(enode, len) {
+			Ok(enode) => enode,
+			Err(err) => return err as c_int,
+		};
+		match client.client.remove_reserved_peer(enode) {
+			Ok(()) => ParityError::Ok as c_int,
+			Err(err) => {
+				set_last_error(err);
+				ParityError::InvalidValue as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_p2p_port(client: *mut c_void, out_port: *mut u16, out_udp_port: *mut u16) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let (port, udp_port) = match client.client.p2p_ports() {
+			Some(ports) => ports,
+			None => {
+				set_last_error("The network is disabled");
+				return ParityError::NetworkDisabled as c_int;
+			},
+		};
+
+		*out_port = port;
+		if !out_udp_port.is_null() {
+			*out_udp_port = udp_port;
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_jsonrpc_http_address(client: *mut c_void, out_ptr: *mut *mut c_char, out_len: *mut usize) -> c_int {
+	panic::catch_unwind(|| {
+		*out_ptr = ptr::null_mut();
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let address = match client.client.jsonrpc_http_address() {
+			Some(address) => address.to_string(),
+			None => {
+				set_last_error("The HTTP JSON-RPC server is disabled");
+				return ParityError::HttpServerDisabled as c_int;
+			},
+		};
+
+		*out_len = address.len();
+		*out_ptr = CString::new(address).expect("socket addresses never contain a null byte; qed").into_raw();
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_string_destroy(s: *mut c_char) {
+	if s.is_null() {
+		return;
+	}
+
+	let _ = panic::catch_unwind(|| {
+		let _s = CString::from_raw(s);
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_version(out_ptr: *mut *const c_char, out_len: *mut usize) {
+	let _ = panic::catch_unwind(|| {
+		*out_ptr = VERSION.as_ptr();
+		*out_len = VERSION.as_bytes().len();
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_keccak256(input: *const u8, len: usize, out_hash: *mut u8) {
+	let _ = panic::catch_unwind(|| {
+		let input = if len == 0 { &[][..] } else { slice::from_raw_parts(input, len) };
+		let hash = keccak_hash::keccak(input);
+		ptr::copy_nonoverlapping(hash.as_ptr(), out_hash, 32);
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_last_error_message(out_ptr: *mut *const c_char, out_len: *mut usize) {
+	let _ = panic::catch_unwind(|| {
+		LAST_ERROR.with(|last_error| {
+			match *last_error.borrow() {
+				Some(ref msg) => {
+					*out_ptr = msg.as_ptr();
+					*out_len = msg.as_bytes().len();
+				},
+				None => {
+					*out_ptr = b"\0".as_ptr() as *const c_char;
+					*out_len = 0;
+				},
+			}
+		});
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_logger(callback: Option<extern "C" fn(*mut c_void, c_int, *const c_char, usize, *const c_char, usize)>, userdata: *mut c_void, filter: *const c_char, filter_len: usize) -> c_int {
+	panic::catch_unwind(|| {
+		let callback = match callback {
+			Some(callback) => callback,
+			None => return null_callback() as c_int,
+		};
+
+		let filter = {
+			let string = slice::from_raw_parts(filter as *const u8, filter_len);
+			match str::from_utf8(string) {
+				Ok(f) => f,
+				Err(err) => {
+					set_last_error(err);
+					return ParityError::InvalidUtf8 as c_int;
+				},
+			}
+		};
+
+		// Same defaults as the logger of the parity binary.
+		let mut builder = FilterBuilder::new();
+		builder.filter(Some("ws"), LevelFilter::Warn);
+		builder.filter(Some("hyper"), LevelFilter::Warn);
+		builder.filter(Some("rustls"), LevelFilter::Error);
+		builder.filter(None, LevelFilter::Info);
+		builder.parse(filter);
+
+		let logger = CallbackLogger { callback, userdata, filter: builder.build() };
+		let max_level = logger.filter.filter();
+
+		match log::set_boxed_logger(Box::new(logger)) {
+			Ok(()) => {
+				log::set_max_level(max_level);
+				ParityError::Ok as c_int
+			},
+			Err(err) => {
+				set_last_error(err);
+				ParityError::LoggerAlreadySet as c_int
+			},
+		}
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_panic_hook(callback: extern "C" fn(*mut c_void, *const c_char, usize), param: *mut c_void) {
+	let cb = CallbackStr(Some(callback), param);
+	let mut previous_hook = PREVIOUS_PANIC_HOOK.lock().expect("panic hook lock poisoned");
+	if previous_hook.is_none() {
+		*previous_hook = Some(Arc::new(panic::take_hook()));
+	}
+	panic_hook::set_with(move |panic_msg| {
+		cb.call(panic_msg);
+		abort_if_requested(panic_msg);
+	});
+}
+
+#[no_mangle]
+pub extern fn parity_set_panic_mode(mode: c_int) -> c_int {
+	panic::catch_unwind(|| {
+		match mode {
+			PANIC_MODE_RECOVER => ABORT_ON_PANIC.store(false, Ordering::SeqCst),
+			PANIC_MODE_ABORT => {
+				watch_panics();
+				ABORT_ON_PANIC.store(true, Ordering::SeqCst);
+			},
+			_ => {
+				set_last_error(format!("Unknown panic mode {}", mode));
+				return ParityError::InvalidValue as c_int;
+			},
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub extern fn parity_clear_panic_hook() {
+	let _ = panic::catch_unwind(|| {
+		let mut previous_hook = PREVIOUS_PANIC_HOOK.lock().expect("panic hook lock poisoned");
+		if let Some(previous_hook) = previous_hook.take() {
+			panic::set_hook(Box::new(move |info| previous_hook(info)));
+		}
+		ABORT_ON_PANIC.store(false, Ordering::SeqCst);
+	});
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_shutdown_callback(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void)>, custom: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = callback.map(|callback| CallbackShutdown(callback, custom));
+		*client.shutdown_callback.lock().expect("shutdown callback lock poisoned") = callback;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_db_error_callback(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void, *const c_char, usize)>, custom: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = callback.map(|callback| CallbackStr(Some(callback), custom));
+		*client.db_error_callback.lock().expect("database error callback lock poisoned") = callback;
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+#[no_mangle]
+pub unsafe extern fn parity_set_rpc_ready_callback(client: *mut c_void, callback: Option<extern "C" fn(*mut c_void)>, custom: *mut c_void) -> c_int {
+	panic::catch_unwind(|| {
+		let client: &ParityClient = match handle_ref(client) {
+			Ok(client) => client,
+			Err(err) => return err as c_int,
+		};
+
+		let callback = callback.map(|callback| CallbackReady(callback, custom));
+		*client.rpc_ready.lock().expect("rpc ready callback lock poisoned") = callback;
+		if let Some(callback) = callback {
+			client.client.add_rpc_ready_listener(move || callback.call());
+		}
+		ParityError::Ok as c_int
+	}).unwrap_or(ParityError::Panic as c_int)
+}
+
+// Handle to a running client, as returned by `parity_start`.
+struct ParityClient {
+	client: RunningClient,
+	// Configuration and restart callback the client was started with, reused by `parity_restart`.
+	config: parity_ethereum::Configuration,
+	on_client_restart: CallbackStr,
+	rpc_timeout_ms: AtomicUsize,
+	// Whether the network was stopped by `parity_network_pause`.
+	network_paused: AtomicBool,
+	// Callback set with `parity_set_rpc_ready_callback`, called again once restarted.
+	rpc_ready: Mutex<Option<CallbackReady>>,
+	// Callback set with `parity_set_shutdown_callback`, taken when it fires or the client is
+	// destroyed.
+	shutdown_callback: Arc<Mutex<Option<CallbackShutdown>>>,
+	// Callback set with `parity_set_db_error_callback`, taken when it fires or the client is
+	// destroyed.
+	db_error_callback: Arc<Mutex<Option<CallbackStr>>>,
+	// Base directory of an in-memory client without a base path of its own, removed once the
+	// client is shut down.
+	temp_dir: Option<TempDir>,
+	subscriptions: Mutex<HashMap<usize, Subscription>>,
+	next_subscription_id: AtomicUsize,
+	new_block_listeners: Mutex<HashMap<usize, NewBlockListener>>,
+	log_listeners: Mutex<HashMap<usize, LogListener>>,
+	next_listener_id: AtomicUsize,
+	// Stops the task reporting the sync progress when dropped.
+	sync_progress_reporter: Mutex<Option<oneshot::Sender<()>>>,
+	// Runtime driving the RPC queries, created on first use.
+	runtime: Mutex<Option<Runtime>>,
+}
+
+impl ParityClient {
+	fn new(client: RunningClient, config: parity_ethereum::Configuration, on_client_restart: CallbackStr, temp_dir: Option<TempDir>) -> Self {
+		let shutdown_callback: Arc<Mutex<Option<CallbackShutdown>>> = Arc::new(Mutex::new(None));
+		let on_shutdown = shutdown_callback.clone();
+		client.add_shutdown_listener(move || {
+			if let Some(callback) = on_shutdown.lock().expect("shutdown callback lock poisoned").take() {
+				callback.call();
+			}
+		});
+		let db_error_callback: Arc<Mutex<Option<CallbackStr>>> = Arc::new(Mutex::new(None));
+		let on_database_error = db_error_callback.clone();
+		client.add_database_error_listener(move |error| {
+			if let Some(callback) = on_database_error.lock().expect("database error callback lock poisoned").take() {
+				callback.call(error);
+			}
+		});
+
+		ParityClient {
+			client,
+			config,
+			on_client_restart,
+			rpc_timeout_ms: AtomicUsize::new(QUERY_TIMEOUT_MS),
+			network_paused: AtomicBool::new(false),
+			rpc_ready: Mutex::new(None),
+			shutdown_callback,
+			db_error_callback,
+			temp_dir,
+			subscriptions: Mutex::new(HashMap::new()),
+			next_subscription_id: AtomicUsize::new(1),
+			new_block_listeners: Mutex::new(HashMap::new()),
+			log_listeners: Mutex::new(HashMap::new()),
+			next_listener_id: AtomicUsize::new(1),
+			sync_progress_reporter: Mutex::new(None),
+			runtime: Mutex::new(None),
+		}
+	}
+
+	fn executor(&self) -> Executor {
+		let mut runtime = self.runtime.lock().expect("runtime lock poisoned");
+		runtime.get_or_insert_with(|| match RPC_THREAD_COUNT.load(Ordering::SeqCst) {
+			0 => Runtime::with_default_thread_count(),
+			thread_count => Runtime::with_thread_count(thread_count),
+		}).executor()
+	}
+
+	fn rpc_timeout(&self) -> Duration {
+		Duration::from_millis(self.rpc_timeout_ms.load(Ordering::SeqCst) as u64)
+	}
+
+	// Performs an RPC query on the current thread, blocking until it completes or times out.
+	fn rpc_query(&self, query: &str, timeout: Duration) -> Result<String, ParityError> {
+		wait_for(self.client.rpc_query(query, None), timeout)?.ok_or_else(|| {
+			set_last_error("The request got no response");
+			ParityError::RpcFailed
+		})
+	}
+
+	// Spawns an RPC query on the shared runtime. Its outcome is passed to `on_result`.
+	fn spawn_query<F>(&self, query: &str, timeout: Duration, on_result: F)
+		where F: FnOnce(Result<String, ParityError>) + Send + 'static
+	{
+		// Either the query or the timeout completes, but both need to own the callback.
+		let on_result = Arc::new(Mutex::new(Some(on_result)));
+		let on_timeout = on_result.clone();
+		let future = self.client.rpc_query(query, None);
+
+		self.executor().spawn_with_timeout(
+			move || future.map(move |response| {
+				if let Some(on_result) = on_result.lock().expect("query callback lock poisoned").take() {
+					on_result(response.ok_or(ParityError::RpcFailed));
+				}
+			}),
+			timeout,
+			move || {
+				if let Some(on_result) = on_timeout.lock().expect("query callback lock poisoned").take() {
+					on_result(Err(ParityError::Timeout));
+				}
+			},
+		);
+	}
+
+	// Performs a subscription request. `on_message` is first called with the response to the
+	// request from the current thread, then with each notification from a dedicated thread.
+	fn subscribe<F>(&self, query: &str, on_message: F) -> Result<usize, ParityError>
+		where F: Fn(&str) + Send + 'static
+	{
+		let (tx, rx) = futures_mpsc::channel(SUBSCRIPTION_BUFFER);
+		let session = Arc::new(PubSubSession::new(tx));
+
+		// The subscription request is answered immediately with the subscription id.
+		let future = self.client.rpc_query(query, Some(session.clone()));
+		let response = match wait_for(future, self.rpc_timeout()) {
+			Ok(Some(response)) => response,
+			Ok(None) => {
+				set_last_error("The subscription request got no response");
+				return Err(ParityError::RpcFailed);
+			},
+			Err(err) => return Err(err),
+		};
+		if let Some(error) = rpc_failure(&response) {
+			set_last_error(error);
+			return Err(ParityError::RpcFailed);
+		}
+		on_message(&response);
+
+		// The notifications are moved from the session to the queue as soon as they arrive, so that
+		// the queue alone decides what happens when the callback can't keep up. Dropping the
+		// subscription discards the queue and waits for the delivery thread, which drops
+		// `on_message` when it ends.
+		let queue = Arc::new(NotificationQueue::new());
+		let thread_name = match query_method(query) {
+			Some(method) => format!("rpc-{}", method),
+			None => "rpc-subscriber".into(),
+		};
+		let receiver_queue = queue.clone();
+		thread::Builder::new()
+			.name(format!("{}-receiver", thread_name))
+			.spawn(move || {
+				for notification in rx.wait() {
+					if let Ok(notification) = notification {
+						receiver_queue.push(notification);
+					}
+				}
+				receiver_queue.close();
+			})
+			.expect("rpc-subscriber thread shouldn't fail; qed");
+		let delivery_queue = queue.clone();
+		let delivery = thread::Builder::new()
+			.name(thread_name)
+			.spawn(move || {
+				while let Some((notification, dropped)) = delivery_queue.pop() {
+					if let Some((count, on_dropped)) = dropped {
+						on_dropped.call(count);
+					}
+					on_message(&notification);
+				}
+			})
+			.expect("rpc-subscriber thread shouldn't fail; qed");
+
+		Ok(self.add_subscription(Subscription { session, queue, delivery: Some(delivery) }))
+	}
+
+	fn add_subscription(&self, subscription: Subscription) -> usize {
+		let id = self.next_subscription_id.fetch_add(1, Ordering::SeqCst);
+		self.subscriptions.lock().expect("subscriptions lock poisoned").insert(id, subscription);
+		id
+	}
+
+	fn set_subscription_buffer(&self, id: usize, max: usize, policy: BufferPolicy, on_dropped: Option<CallbackDropped>) -> Result<(), ParityError> {
+		match self.subscriptions.lock().expect("subscriptions lock poisoned").get(&id) {
+			Some(subscription) => {
+				subscription.queue.set_limit(max, policy, on_dropped);
+				Ok(())
+			},
+			None => {
+				set_last_error(format!("No subscription with the id {}", id));
+				Err(ParityError::InvalidValue)
+			},
+		}
+	}
+
+	fn remove_subscription(&self, id: usize) {
+		// The subscription is dropped once the lock is released, as its callback may still be
+		// delivering a notification and calling into the client.
+		let subscription = self.subscriptions.lock().expect("subscriptions lock poisoned").remove(&id);
+		drop(subscription);
+	}
+
+	fn add_new_block_listener(&self, listener: NewBlockListener) -> usize {
+		let id = self.next_listener_id.fetch_add(1, Ordering::SeqCst);
+		self.new_block_listeners.lock().expect("listeners lock poisoned").insert(id, listener);
+		id
+	}
+
+	fn remove_new_block_listener(&self, id: usize) {
+		// Dropping the listener unregisters it from the client.
+		self.new_block_listeners.lock().expect("listeners lock poisoned").remove(&id);
+	}
+
+	fn add_log_listener(&self, listener: LogListener) -> usize {
+		let id = self.next_listener_id.fetch_add(1, Ordering::SeqCst);
+		self.log_listeners.lock().expect("listeners lock poisoned").insert(id, listener);
+		id
+	}
+
+	fn remove_log_listener(&self, id: usize) {
+		// Dropping the listener unregisters it from the client.
+		self.log_listeners.lock().expect("listeners lock poisoned").remove(&id);
+	}
+
+	// Replaces the task reporting the sync progress, if any.
+	fn set_sync_progress_reporter(&self, reporter: Option<oneshot::Sender<()>>) {
+		*self.sync_progress_reporter.lock().expect("sync progress reporter lock poisoned") = reporter;
+	}
+
+	fn shutdown(self) {
+		// Destroying a client doesn't fire its shutdown and database error callbacks.
+		self.shutdown_callback.lock().expect("shutdown callback lock poisoned").take();
+		self.db_error_callback.lock().expect("database error callback lock poisoned").take();
+		// Waits for the callbacks of the subscriptions, which may still be running.
+		drop(self.subscriptions);
+		drop(self.new_block_listeners);
+		drop(self.log_listeners);
+		drop(self.sync_progress_reporter);
+		drop(self.runtime);
+		self.client.shutdown();
+		drop(self.temp_dir);
+	}
+}
+
+// Subscription made with `parity_subscribe`. Once it has been dropped, its callback isn't running
+// and won't be called anymore.
+struct Subscription {
+	// Dropping the session cancels its subscriptions.
+	session: Arc<PubSubSession>,
+	queue: Arc<NotificationQueue>,
+	// Thread passing the notifications to the callback.
+	delivery: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for Subscription {
+	fn drop(&mut self) {
+		self.queue.discard();
+		if let Some(delivery) = self.delivery.take() {
+			// The callback may be the one cancelling the subscription, in which case it returns to
+			// a thread that stops right away.
+			if delivery.thread().id() != thread::current().id() {
+				let _ = delivery.join();
+			}
+		}
+	}
+}
+
+// What a `NotificationQueue` does with a notification once it holds its maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BufferPolicy {
+	// Waits for room in the queue.
+	Block,
+	// Drops the oldest queued notification to make room.
+	DropOldest,
+	// Drops the notification being queued.
+	DropNewest,
+}
+
+// Notifications of a subscription waiting to be passed to its callback. The queue blocks or drops
+// notifications once it holds its maximum, as set with `parity_set_subscription_buffer`.
+struct NotificationQueue {
+	state: Mutex<NotificationQueueState>,
+	// Signalled when a notification is queued or no more notifications will be.
+	queued: Condvar,
+	// Signalled when a notification is taken from the queue or the limit changes.
+	taken: Condvar,
+}
+
+struct NotificationQueueState {
+	notifications: VecDeque<String>,
+	max: usize,
+	policy: BufferPolicy,
+	// Number of notifications dropped since the last call to `on_dropped`.
+	dropped: usize,
+	on_dropped: Option<CallbackDropped>,
+	closed: bool,
+}
+
+impl NotificationQueue {
+	fn new() -> Self {
+		NotificationQueue {
+			state: Mutex::new(NotificationQueueState {
+				notifications: VecDeque::new(),
+				max: SUBSCRIPTION_BUFFER,
+				policy: BufferPolicy::Block,
+				dropped: 0,
+				on_dropped: None,
+				closed: false,
+			}),
+			queued: Condvar::new(),
+			taken: Condvar::new(),
+		}
+	}
+
+	// Queues a notification, once there is room for it. Does nothing once the queue is closed.
+	fn push(&self, notification: String) {
+		let mut state = self.state.lock().expect("notification queue lock poisoned");
+		if state.closed {
+			return;
+		}
+		while state.notifications.len() >= state.max {
+			match state.policy {
+				BufferPolicy::Block => {
+					state = self.taken.wait(state).expect("notification queue lock poisoned");
+					if state.closed {
+						return;
+					}
+				},
+				BufferPolicy::DropOldest => {
+					state.notifications.pop_front();
+					state.dropped += 1;
+				},
+				BufferPolicy::DropNewest => {
+					state.dropped += 1;
+					return;
+				},
+			}
+		}
+		state.notifications.push_back(notification);
+		self.queued.notify_one();
+	}
+
+	// Marks the end of the notifications. Those still queued can be taken.
+	fn close(&self) {
+		self.state.lock().expect("notification queue lock poisoned").closed = true;
+		self.queued.notify_all();
+	}
+
+	// Closes the queue and drops the notifications still queued.
+	fn discard(&self) {
+		let mut state = self.state.lock().expect("notification queue lock poisoned");
+		state.closed = true;
+		state.notifications.clear();
+		self.queued.notify_all();
+		self.taken.notify_all();
+	}
+
+	// Waits for the next notification, along with the number of notifications dropped before it
+	// and the callback to report them to, if any. Returns `None` once the queue is closed and empty.
+	fn pop(&self) -> Option<(String, Option<(usize, CallbackDropped)>)> {
+		let mut state = self.state.lock().expect("notification queue lock poisoned");
+		loop {
+			if let Some(notification) = state.notifications.pop_front() {
+				let dropped = match (state.dropped, state.on_dropped) {
+					(0, _) | (_, None) => None,
+					(count, Some(on_dropped)) => Some((count, on_dropped)),
+				};
+				state.dropped = 0;
+				self.taken.notify_all();
+				return Some((notification, dropped));
+			}
+			if state.closed {
+				return None;
+			}
+			state = self.queued.wait(state).expect("notification queue lock poisoned");
+		}
+	}
+
+	fn set_limit(&self, max: usize, policy: BufferPolicy, on_dropped: Option<CallbackDropped>) {
+		let mut state = self.state.lock().expect("notification queue lock poisoned");
+		state.max = max;
+		state.policy = policy;
+		state.on_dropped = on_dropped;
+		match policy {
+			BufferPolicy::Block => {},
+			BufferPolicy::DropOldest => while state.notifications.len() > max {
+				state.notifications.pop_front();
+				state.dropped += 1;
+			},
+			BufferPolicy::DropNewest => while state.notifications.len() > max {
+				state.notifications.pop_back();
+				state.dropped += 1;
+			},
+		}
+		self.taken.notify_all();
+	}
+}
+
+// Starts a client taking over from one that was shut down, with its RPC timeout and RPC ready
+// callback, which is called once the servers of the new client are bound.
+fn start_replacement_client(config: parity_ethereum::Configuration, on_client_restart: CallbackStr, rpc_timeout_ms: usize, rpc_ready: Option<CallbackReady>) -> Result<*mut c_void, ParityError> {
+	let new_client = match start_client(config, on_client_restart)? {
+		Some(new_client) => new_client,
+		None => {
+			set_last_error("The client exited right after starting");
+			return Err(ParityError::StartFailed);
+		},
+	};
+
+	let new_client_ref: &ParityClient = unsafe { handle_ref(new_client) }.expect("start_client returns client handles; qed");
+	new_client_ref.rpc_timeout_ms.store(rpc_timeout_ms, Ordering::SeqCst);
+	if let Some(rpc_ready) = rpc_ready {
+		*new_client_ref.rpc_ready.lock().expect("rpc ready callback lock poisoned") = Some(rpc_ready);
+		new_client_ref.client.add_rpc_ready_listener(move || rpc_ready.call());
+	}
+	Ok(new_client)
+}
+
+// Starts a client and registers its handle, or returns `None` if the execution completed
+// instantly.
+fn start_client(config: parity_ethereum::Configuration, on_client_restart: CallbackStr) -> Result<Option<*mut c_void>, ParityError> {
+	let on_client_restart_cb = move |new_chain: String| { on_client_restart.call(&new_chain); };
+
+	// The other files of an in-memory client go to a temporary directory, so that none is left.
+	let mut run_config = config.clone();
+	let temp_dir = if config.in_memory && config.args.arg_base_path.is_none() && config.args.arg_datadir.is_none() {
+		let temp_dir = TempDir::new("parity").map_err(|err| {
+			set_last_error(format!("Cannot create a temporary directory: {}", err));
+			ParityError::StartFailed
+		})?;
+		run_config.args.arg_base_path = Some(temp_dir.path().to_string_lossy().into_owned());
+		Some(temp_dir)
+	} else {
+		None
+	};
+
+	let action = match parity_ethereum::start(run_config, on_client_restart_cb, || {}) {
+		Ok(action) => action,
+		Err(err) => {
+			let code = if err.starts_with(parity_ethereum::DATABASE_LOCKED_ERROR) {
+				ParityError::DatabaseLocked
+			} else if err.starts_with(parity_ethereum::PERMISSION_DENIED_ERROR) {
+				ParityError::PermissionDenied
+			} else {
+				ParityError::StartFailed
+			};
+			set_last_error(err);
+			return Err(code);
+		},
+	};
+
+	match action {
+		parity_ethereum::ExecutionAction::Instant(Some(s)) => { println!("{}", s); Ok(None) },
+		parity_ethereum::ExecutionAction::Instant(None) => Ok(None),
+		parity_ethereum::ExecutionAction::Running(client) => {
+			let client = into_handle(ParityClient::new(client, config, on_client_restart, temp_dir));
+			LIVE_CLIENTS.lock().expect("live clients lock poisoned").insert(client as usize);
+			Ok(Some(client))
+		}
+	}
+}
+
+unsafe fn config_from_args<S: AsRef<str>>(args: &[S], output: *mut *mut c_void) -> c_int {
+	match parity_ethereum::Configuration::parse_cli(args) {
+		Ok(mut cfg) => {
+			// Always disable the auto-updater when used as a library.
+			cfg.args.arg_auto_update = "none".to_owned();
+
+			*output = into_handle(cfg);
+			ParityError::Ok as c_int
+		},
+		Err(err) => {
+			let code = match err {
+				ArgsError::Config(..) => ParityError::ConfigRead,
+				ArgsError::Decode(_) => ParityError::ConfigParse,
+				ArgsError::Clap(_) | ArgsError::PeerConfiguration => ParityError::CliParse,
+			};
+			match err {
+				ArgsError::Clap(err) => set_last_error(clap_error_message(&err.message)),
+				err => set_last_error(err),
+			}
+			code as c_int
+		},
+	}
+}
+
+// Keeps the line of a clap error that names the faulty argument, without the usage that follows
+// it or the terminal colors.
+fn clap_error_message(message: &str) -> String {
+	let mut chars = message.lines().next().unwrap_or("").chars();
+	let mut line = String::new();
+	while let Some(c) = chars.next() {
+		if c == '\u{1b}' {
+			chars.by_ref().find(|c| c.is_ascii_alphabetic());
+		} else {
+			line.push(c);
+		}
+	}
+	line
+}
+
+// Reads a non-empty string value for one of the `parity_config_set_*` functions.
+unsafe fn config_str(value: *const c_char, len: usize) -> Result<String, ParityError> {
+	let value = slice::from_raw_parts(value as *const u8, len);
+	match str::from_utf8(value) {
+		Ok("") => {
+			set_last_error("The value must not be empty");
+			Err(ParityError::InvalidValue)
+		},
+		Ok(value) => Ok(value.to_owned()),
+		Err(err) => {
+			set_last_error(err);
+			Err(ParityError::InvalidUtf8)
+		},
+	}
+}
+
+// Checks that the logger will be able to append to the log file at `path`: either the file exists
+// and isn't read-only, or its directory exists and isn't read-only.
+fn check_log_file(path: &Path) -> Result<(), String> {
+	match fs::metadata(path) {
+		Ok(ref metadata) if metadata.is_dir() => return Err("it is a directory".into()),
+		Ok(ref metadata) if metadata.permissions().readonly() => return Err("it is read-only".into()),
+		Ok(_) => return Ok(()),
+		Err(_) => {},
+	}
+
+	let dir = match path.parent() {
+		Some(dir) if dir != Path::new("") => dir,
+		_ => Path::new("."),
+	};
+	match fs::metadata(dir) {
+		Ok(ref metadata) if !metadata.is_dir() => Err(format!("{} isn't a directory", dir.display())),
+		Ok(ref metadata) if metadata.permissions().readonly() => Err(format!("{} is read-only", dir.display())),
+		Ok(_) => Ok(()),
+		Err(err) => Err(format!("{}: {}", dir.display(), err)),
+	}
+}
+
+// Reads the password of an account, which unlike the configuration values may be empty.
+unsafe fn account_password(password: *const c_char, len: usize) -> Result<String, ParityError> {
+	let password = if len == 0 { &[][..] } else { slice::from_raw_parts(password as *const u8, len) };
+	str::from_utf8(password).map(ToOwned::to_owned).map_err(|err| {
+		set_last_error(err);
+		ParityError::InvalidUtf8
+	})
+}
+
+// Reads a list of values for one of the `parity_config_set_*` functions, joined with commas as on
+// the command line. An empty list gives "none", and "all" must be the only value of its list.
+unsafe fn config_list(values: *const *const c_char, lens: *const usize, count: usize) -> Result<String, ParityError> {
+	if count == 0 {
+		return Ok("none".to_owned());
+	}
+
+	let value_ptrs = slice::from_raw_parts(values, count);
+	let value_lens = slice::from_raw_parts(lens, count);
+
+	let mut list = Vec::with_capacity(count);
+	for (&value, &len) in value_ptrs.iter().zip(value_lens.iter()) {
+		let value = config_str(value, len)?;
+		if value.contains(',') || value.chars().any(char::is_whitespace) {
+			set_last_error(format!("Invalid value {:?}: values can't contain commas or whitespace", value));
+			return Err(ParityError::InvalidValue);
+		}
+		list.push(value);
+	}
+
+	let is_special = |value: &String| match value.as_str() {
+		"none" | "*" | "all" | "any" => true,
+		_ => false,
+	};
+	if list.len() > 1 && list.iter().any(is_special) {
+		set_last_error("\"all\" and \"none\" can't be combined with other values");
+		return Err(ParityError::InvalidValue);
+	}
+
+	Ok(list.join(","))
+}
+
+// Forgets a client about to be destroyed. Returns false if it isn't a live client.
+fn unregister_client(client: *mut c_void) -> bool {
+	LIVE_CLIENTS.lock().expect("live clients lock poisoned").remove(&(client as usize))
+}
+
+// Chains the panic hook of the abort mode to the current one, unless the hook of the library is
+// already installed.
+fn watch_panics() {
+	let mut previous_hook = PREVIOUS_PANIC_HOOK.lock().expect("panic hook lock poisoned");
+	if previous_hook.is_some() {
+		return;
+	}
+
+	let hook = Arc::new(panic::take_hook());
+	*previous_hook = Some(hook.clone());
+	panic::set_hook(Box::new(move |info| {
+		hook(info);
+		abort_if_requested(&panic_hook::gen_panic_msg(info));
+	}));
+}
+
+// Called from the panic hook, once the previous hook or the callback of `parity_set_panic_hook`
+// ran: in the abort mode of `parity_set_panic_mode`, the process stops before the panic unwinds to
+// `catch_unwind`.
+fn abort_if_requested(panic_msg: &str) {
+	if ABORT_ON_PANIC.load(Ordering::SeqCst) {
+		let _ = io::stderr().write_all(panic_msg.as_bytes());
+		process::abort();
+	}
+}
+
+// Allows moving a client to another thread; the C API already lets any thread use the handle.
+struct SendClient(ParityClient);
+unsafe impl Send for SendClient {}
+
+fn set_last_error<E: fmt::Display>(err: E) {
+	LAST_ERROR.with(|last_error| {
+		*last_error.borrow_mut() = CString::new(err.to_string()).ok();
+	});
+}
+
+// Formats the metrics returned by `parity_metrics_prometheus`, in the Prometheus text format.
+fn prometheus_metrics(client: &RunningClient) -> String {
+	let sync_status = client.sync_status();
+	let (pending, queued) = client.transaction_queue_status();
+	let metrics = [
+		("parity_chain_best_block", "Number of the best block imported locally.", sync_status.current_block),
+		("parity_sync_highest_block", "Number of the highest block seen on the network.", sync_status.highest_block),
+		("parity_sync_is_syncing", "Whether the client is performing a major sync.", sync_status.is_syncing as u64),
+		("parity_network_peers", "Number of connected peers.", client.peer_count() as u64),
+		("parity_txqueue_pending", "Number of queued transactions ready to be included in the next block.", pending as u64),
+		("parity_txqueue_queued", "Number of queued transactions waiting for a transaction with a lower nonce.", queued as u64),
+	];
+
+	let mut output = String::new();
+	for &(name, help, value) in &metrics {
+		output.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n{} {}\n", name, help, name, name, value));
+	}
+	output
+}
+
+// Writes a 256-bit value as 32 big-endian bytes to `out_wei`, or reports why there is none.
+unsafe fn u256_result(value: Option<U256>, out_wei: *mut u8, unavailable: &str) -> c_int {
+	match value {
+		Some(value) => {
+			value.to_big_endian(slice::from_raw_parts_mut(out_wei, 32));
+			ParityError::Ok as c_int
+		},
+		None => {
+			set_last_error(unavailable);
+			ParityError::Unavailable as c_int
+		},
+	}
+}
+
+// Converts the outcome of a `RunningClient` method updating the miner to a `ParityError` code.
+fn miner_result(result: Result<(), String>) -> c_int {
+	match result {
+		Ok(()) => ParityError::Ok as c_int,
+		Err(err) => client_error(err, ParityError::InvalidValue) as c_int,
+	}
+}
+
+// Returns the code of an error of a `RunningClient` method, and stores its message. Errors
+// unrelated to the support of the method by the client get the `other` code.
+fn client_error(err: String, other: ParityError) -> ParityError {
+	let code = if err == parity_ethereum::LIGHT_CLIENT_UNSUPPORTED_ERROR || err == parity_ethereum::SEAL_ON_DEMAND_UNSUPPORTED_ERROR {
+		ParityError::Unsupported
+	} else {
+		other
+	};
+	set_last_error(err);
+	code
+}
+
+// Reports that a pointer a function needs is NULL.
+fn null_pointer() -> ParityError {
+	set_last_error("A pointer passed to the function is NULL");
+	ParityError::NullPointer
+}
+
+// Reports that a callback a function needs is NULL.
+fn null_callback() -> ParityError {
+	set_last_error("The callback is NULL");
+	ParityError::NullCallback
+}
+
+unsafe fn parse_json_query<'a>(query: *const c_char, len: usize) -> Result<&'a str, ParityError> {
+	let string = slice::from_raw_parts(query as *const u8, len);
+	str::from_utf8(string).map_err(|err| {
+		set_last_error(err);
+		ParityError::InvalidUtf8
+	})
+}
+
+// Extracts the `method` of a JSON-RPC request without parsing the whole request. Only returns
+// names that are suitable for naming a thread.
+fn query_method(query: &str) -> Option<&str> {
+	let key = "\"method\"";
+	let after_key = query[query.find(key)? + key.len()..].trim_left();
+	if !after_key.starts_with(':') {
+		return None;
+	}
+
+	let value = after_key[1..].trim_left();
+	if !value.starts_with('"') {
+		return None;
+	}
+
+	let value = &value[1..];
+	let method = &value[..value.find('"')?];
+	if method.is_empty() || !method.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+		return None;
+	}
+	Some(method)
+}
+
+// Object behind a handle returned to the caller, prefixed with a tag identifying its type so that a
+// handle passed where another type is expected is rejected instead of being misinterpreted.
+#[repr(C)]
+struct Handle<T> {
+	tag: u32,
+	value: T,
+}
+
+// Types of the objects handed out as handles.
+trait HandleType {
+	const TAG: u32;
+}
+
+impl HandleType for parity_ethereum::Configuration {
+	const TAG: u32 = 0x7061_6366; // "pacf"
+}
+
+impl HandleType for ParityClient {
+	const TAG: u32 = 0x7061_636c; // "pacl"
+}
+
+impl HandleType for PolledQuery {
+	const TAG: u32 = 0x7061_7071; // "papq"
+}
+
+// Moves `value` to the heap and returns its handle.
+fn into_handle<T: HandleType>(value: T) -> *mut c_void {
+	let handle = Box::into_raw(Box::new(Handle { tag: T::TAG, value })) as *mut c_void;
+	LIVE_HANDLES.lock().expect("live handles lock poisoned").insert(handle as usize);
+	handle
+}
+
+// Checks that `handle` isn't NULL and was returned by `into_handle` for the type `T` and not taken
+// back since.
+unsafe fn check_handle<T: HandleType>(handle: *const c_void) -> Result<(), ParityError> {
+	if handle.is_null() {
+		set_last_error("The handle is NULL");
+		return Err(ParityError::NullPointer);
+	}
+	// The object behind a destroyed handle is gone, so its tag can't be read. The lock is held
+	// while reading it, so that `take_handle` can't free the object meanwhile.
+	let live_handles = LIVE_HANDLES.lock().expect("live handles lock poisoned");
+	if !live_handles.contains(&(handle as usize)) {
+		set_last_error("The handle was destroyed or wasn't returned by Parity");
+		return Err(ParityError::WrongHandleType);
+	}
+	// The tag is the first field of every `Handle`, whatever the type of its value.
+	if *(handle as *const u32) != T::TAG {
+		set_last_error("The handle doesn't point to an object of the expected type");
+		return Err(ParityError::WrongHandleType);
+	}
+	Ok(())
+}
+
+unsafe fn handle_ref<'a, T: HandleType>(handle: *const c_void) -> Result<&'a T, ParityError> {
+	check_handle::<T>(handle)?;
+	Ok(&(*(handle as *const Handle<T>)).value)
+}
+
+unsafe fn handle_mut<'a, T: HandleType>(handle: *mut c_void) -> Result<&'a mut T, ParityError> {
+	check_handle::<T>(handle)?;
+	Ok(&mut (*(handle as *mut Handle<T>)).value)
+}
+
+// Takes back the ownership of the object behind `handle`, which mustn't be used afterwards.
+unsafe fn take_handle<T: HandleType>(handle: *mut c_void) -> Result<T, ParityError> {
+	check_handle::<T>(handle)?;
+	// Only one of two threads taking the same handle back at the same time gets it.
+	if !LIVE_HANDLES.lock().expect("live handles lock poisoned").remove(&(handle as usize)) {
+		set_last_error("The handle was destroyed or wasn't returned by Parity");
+		return Err(ParityError::WrongHandleType);
+	}
+	let Handle { value, .. } = *Box::from_raw(handle as *mut Handle<T>);
+	Ok(value)
+}
+
+// Internal structure for handling callbacks that get passed a string.
+#[derive(Clone, Copy)]
+struct CallbackStr(Option<extern "C" fn(*mut c_void, *const c_char, usize)>, *mut c_void);
+unsafe impl Send for CallbackStr {}
+unsafe impl Sync for CallbackStr {}
+impl CallbackStr {
+	fn call(&self, new_chain: &str) {
+		if let Some(ref cb) = self.0 {
+			cb(self.1, new_chain.as_bytes().as_ptr() as *const _, new_chain.len())
+		}
+	}
+
+	fn call_null(&self) {
+		if let Some(ref cb) = self.0 {
+			cb(self.1, ptr::null(), 0)
+		}
+	}
+}
+
+// Logger forwarding the records to a callback provided by the user.
+struct CallbackLogger {
+	callback: extern "C" fn(*mut c_void, c_int, *const c_char, usize, *const c_char, usize),
+	userdata: *mut c_void,
+	filter: Filter,
+}
+unsafe impl Send for CallbackLogger {}
+unsafe impl Sync for CallbackLogger {}
+impl log::Log for CallbackLogger {
+	fn enabled(&self, metadata: &log::Metadata) -> bool {
+		self.filter.enabled(metadata)
+	}
+
+	fn log(&self, record: &log::Record) {
+		if !self.filter.matches(record) {
+			return;
+		}
+
+		let target = record.target();
+		let message = record.args().to_string();
+		(self.callback)(
+			self.userdata,
+			record.level() as c_int,
+			target.as_ptr() as *const _,
+			target.len(),
+			message.as_ptr() as *const _,
+			message.len(),
+		);
+	}
+
+	fn flush(&self) {}
+}
+
+// Internal structure for handling callbacks that get passed the index of a query and its response.
+struct CallbackBatch(extern "C" fn(*mut c_void, usize, *const c_char, usize), *mut c_void);
+unsafe impl Send for CallbackBatch {}
+unsafe impl Sync for CallbackBatch {}
+impl CallbackBatch {
+	fn call(&self, index: usize, response: Option<&str>) {
+		match response {
+			Some(response) => (self.0)(self.1, index, response.as_bytes().as_ptr() as *const _, response.len()),
+			None => (self.0)(self.1, index, ptr::null(), 0),
+		}
+	}
+}
+
+// Internal structure for handling callbacks that get passed the tag of a query, an error code and
+// its response.
+struct CallbackTagged(extern "C" fn(*mut c_void, u64, c_int, *const c_char, usize), *mut c_void);
+unsafe impl Send for CallbackTagged {}
+impl CallbackTagged {
+	fn call(&self, tag: u64, response: Result<&str, ParityError>) {
+		match response {
+			Ok(response) => (self.0)(self.1, tag, ParityError::Ok as c_int, response.as_ptr() as *const _, response.len()),
+			Err(err) => (self.0)(self.1, tag, err as c_int, ptr::null(), 0),
+		}
+	}
+}
+
+// Internal structure for handling callbacks that notify that a client is shutting down.
+struct CallbackShutdown(extern "C" fn(*mut c_void), *mut c_void);
+unsafe impl Send for CallbackShutdown {}
+impl CallbackShutdown {
+	fn call(&self) {
+		(self.0)(self.1)
+	}
+}
+
+// Internal structure for handling callbacks that notify that the RPC servers of a client are ready.
+#[derive(Clone, Copy)]
+struct CallbackReady(extern "C" fn(*mut c_void), *mut c_void);
+unsafe impl Send for CallbackReady {}
+impl CallbackReady {
+	fn call(&self) {
+		(self.0)(self.1)
+	}
+}
+
+// Internal structure for handling callbacks that get passed an error code and the raw bytes of a
+// response.
+struct CallbackBytes(extern "C" fn(*mut c_void, c_int, *const u8, usize), *mut c_void);
+unsafe impl Send for CallbackBytes {}
+impl CallbackBytes {
+	fn call(&self, response: Result<&[u8], ParityError>) {
+		match response {
+			Ok(response) => (self.0)(self.1, ParityError::Ok as c_int, response.as_ptr(), response.len()),
+			Err(err) => (self.0)(self.1, err as c_int, ptr::null(), 0),
+		}
+	}
+}
+
+// Internal structure for handling callbacks that get passed an error code, whether the response is
+// a JSON-RPC error, and the response.
+struct CallbackRpc(extern "C" fn(*mut c_void, c_int, c_int, *const c_char, usize), *mut c_void);
+unsafe impl Send for CallbackRpc {}
+impl CallbackRpc {
+	fn call(&self, response: Result<&str, ParityError>) {
+		match response {
+			Ok(response) => {
+				let is_error = is_rpc_error(response) as c_int;
+				(self.0)(self.1, ParityError::Ok as c_int, is_error, response.as_ptr() as *const _, response.len())
+			},
+			Err(err) => (self.0)(self.1, err as c_int, 0, ptr::null(), 0),
+		}
+	}
+}
+
+// Returns true if `response` is a single JSON-RPC response carrying an error. Batch responses
+// can mix successes and errors, so they're never considered errors as a whole.
+fn is_rpc_error(response: &str) -> bool {
+	match serde_json::from_str::<serde_json::Value>(response) {
+		Ok(serde_json::Value::Object(ref response)) => response.contains_key("error"),
+		_ => false,
+	}
+}
+
+// Returns why a JSON-RPC response has no result: the message of its error, or the response itself
+// if it has no error message either.
+fn rpc_failure(response: &str) -> Option<String> {
+	let response = match serde_json::from_str::<serde_json::Value>(response) {
+		Ok(serde_json::Value::Object(response)) => response,
+		_ => return Some(response.to_owned()),
+	};
+	if response.contains_key("result") {
+		return None;
+	}
+	match response.get("error").and_then(|error| error.get("message")).and_then(|message| message.as_str()) {
+		Some(message) => Some(message.to_owned()),
+		None => Some(serde_json::Value::Object(response).to_string()),
+	}
+}
+
+// Internal structure for handling callbacks that get passed the number and hash of a new block.
+struct CallbackBlock(extern "C" fn(*mut c_void, u64, *const u8), *mut c_void);
+unsafe impl Send for CallbackBlock {}
+unsafe impl Sync for CallbackBlock {}
+impl CallbackBlock {
+	fn call(&self, number: u64, hash: &[u8]) {
+		(self.0)(self.1, number, hash.as_ptr())
+	}
+}
+
+// Internal structure for handling callbacks that get passed the number of notifications dropped
+// from the buffer of a subscription.
+#[derive(Clone, Copy)]
+struct CallbackDropped(extern "C" fn(*mut c_void, usize), *mut c_void);
+unsafe impl Send for CallbackDropped {}
+impl CallbackDropped {
+	fn call(&self, count: usize) {
+		(self.0)(self.1, count)
+	}
+}
+
+// Internal structure for handling callbacks that get passed a log matching a filter.
+struct CallbackLog(extern "C" fn(*mut c_void, *const ParityLog), *mut c_void);
+unsafe impl Send for CallbackLog {}
+unsafe impl Sync for CallbackLog {}
+impl CallbackLog {
+	fn call(&self, log: &ParityLog) {
+		(self.0)(self.1, log)
+	}
+}
+
+type PolledFuture = Box<Future<Item = Option<String>, Error = ()> + Send>;
+
+// Query started by `parity_rpc_begin`, which the host drives by calling `parity_rpc_poll`.
+struct PolledQuery {
+	future: Spawn<PolledFuture>,
+	notify: Arc<NoopNotify>,
+	// The query times out if it didn't complete when polled after this instant.
+	deadline: Instant,
+	response: Option<Result<String, ParityError>>,
+}
+
+// The host polls the queries on its own schedule, so there is nobody to wake up.
+struct NoopNotify;
+impl Notify for NoopNotify {
+	fn notify(&self, _id: usize) {}
+}
+
+// Wakes up the thread waiting for a future in `wait_for`.
+struct ThreadNotify(thread::Thread);
+impl Notify for ThreadNotify {
+	fn notify(&self, _id: usize) {
+		self.0.unpark();
+	}
+}
+
+// Drives a future on the current thread until it completes or `timeout` elapses. Unlike the shared
+// runtime, this doesn't need a runtime thread to be free, so callbacks running on the runtime can
+// perform queries as well.
+fn wait_for<F: Future>(future: F, timeout: Duration) -> Result<F::Item, ParityError> {
+	let deadline = Instant::now() + timeout;
+	let notify = Arc::new(ThreadNotify(thread::current()));
+	let mut future = executor::spawn(future);
+	loop {
+		match future.poll_future_notify(&notify, 0) {
+			Ok(Async::Ready(item)) => return Ok(item),
+			Err(_) => {
+				set_last_error("The request failed");
+				return Err(ParityError::RpcFailed);
+			},
+			Ok(Async::NotReady) => {
+				let now = Instant::now();
+				if now >= deadline {
+					set_last_error("The request timed out");
+					return Err(ParityError::Timeout);
+				}
+				// Wakes up early when notified, or spuriously, in which case the future is polled again.
+				thread::park_timeout(deadline - now);
+			},
+		}
+	}
+}
+
+// Internal structure for handling callbacks that get passed the sync progress.
+struct CallbackProgress(extern "C" fn(*mut c_void, u64, u64, f64), *mut c_void);
+unsafe impl Send for CallbackProgress {}
+impl CallbackProgress {
+	fn call(&self, current_block: u64, highest_block: u64, blocks_per_sec: f64) {
+		(self.0)(self.1, current_block, highest_block, blocks_per_sec)
+	}
+}
+
+// Internal structure for handling callbacks that get passed the outcome of a transaction submission.
+struct CallbackTransaction(extern "C" fn(*mut c_void, *const u8, *const c_char, usize), *mut c_void);
+impl CallbackTransaction {
+	fn call(&self, result: Result<&[u8], &str>) {
+		match result {
+			Ok(hash) => (self.0)(self.1, hash.as_ptr(), ptr::null(), 0),
+			Err(err) => (self.0)(self.1, ptr::null(), err.as_ptr() as *const c_char, err.len()),
+		}
+	}
+}
+
+// Callback receiving a response, whose context is released once the callback isn't needed anymore.
+#[cfg(feature = "swift")]
+struct CallbackContext {
+	callback: Option<extern "C" fn(*mut c_void, *const c_char, usize)>,
+	context: *mut c_void,
+	release_context: Option<extern "C" fn(*mut c_void)>,
+}
+#[cfg(feature = "swift")]
+unsafe impl Send for CallbackContext {}
+#[cfg(feature = "swift")]
+unsafe impl Sync for CallbackContext {}
+#[cfg(feature = "swift")]
+impl CallbackContext {
+	fn call(&self, response: Option<&str>) {
+		if let Some(ref cb) = self.callback {
+			match response {
+				Some(response) => cb(self.context, response.as_bytes().as_ptr() as *const _, response.len()),
+				None => cb(self.context, ptr::null(), 0),
+			}
+		}
+	}
+}
+#[cfg(feature = "swift")]
+impl Drop for CallbackContext {
+	fn drop(&mut self) {
+		if let Some(ref release) = self.release_context {
+			release(self.context);
+		}
+	}
+}
+
+#[cfg(feature = "jni")]
+#[no_mangle]
+pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_configFromCli(env: JNIEnv, _: JClass, cli: jobjectArray) -> jlong {
+	jni_catch_unwind(&env, || {
+		let cli_len = env.get_array_length(cli).expect("invalid Java bindings");
+
+		let mut jni_strings = Vec::with_capacity(cli_len as usize);
+		let mut opts = Vec::with_capacity(cli_len as usize);
+		let mut opts_lens = Vec::with_capacity(cli_len as usize);
+
+		for n in 0 .. cli_len {
+			let elem = env.get_object_array_element(cli, n).expect("invalid Java bindings");
+			let elem_str: JString = elem.into();
+			match env.get_string(elem_str) {
+				Ok(s) => {
+					opts.push(s.as_ptr());
+					opts_lens.push(s.to_bytes().len());
+					jni_strings.push(s);
+				},
+				Err(err) => {
+					let _ = env.throw_new("java/lang/Exception", err.to_string());
+					return 0
+				}
+			};
+		}
+
+		let mut out = ptr::null_mut();
+		match parity_config_from_cli(opts.as_ptr(), opts_lens.as_ptr(), cli_len as usize, &mut out) {
+			0 => out as usize as jlong,
+			_ => {
+				let _ = env.throw_new("java/lang/Exception", "failed to create config object");
+				0
+			},
+		}
+	}, || 0)
+}
+
+#[cfg(feature = "jni")]
+#[no_mangle]
+pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_build(env: JNIEnv, _: JClass, config: jlong) -> jlong {
+	jni_catch_unwind(&env, || {
+		let params = ParityParams {
+			configuration: config as usize as *mut c_void,
+			.. mem::zeroed()
+		};
+
+		let mut out = ptr::null_mut();
+		match parity_start(&params, &mut out) {
+			0 => out as usize as jlong,
+			_ => {
+				let _ = env.throw_new("java/lang/Exception", "failed to start Parity");
+				0
+			},
+		}
+	}, || 0)
+}
+
+#[cfg(feature = "jni")]
+#[no_mangle]
+pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_destroy(_env: JNIEnv, _: JClass, parity: jlong) {
+	let parity = parity as usize as *mut c_void;
+	parity_destroy(parity);
+}
+
+#[cfg(feature = "jni")]
+#[no_mangle]
+pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_rpcQueryNative<'a>(env: JNIEnv<'a>, _: JClass, parity: jlong, rpc: JString) -> JString<'a> {
+	jni_catch_unwind(&env, || {
+		let client: &ParityClient = match handle_ref(parity as usize as *const c_void) {
+			Ok(client) => client,
+			Err(_) => {
+				let _ = env.throw_new("java/lang/NullPointerException", "the Parity client is null or invalid");
+				return env.new_string("").expect("Creating an empty string never fails");
+			},
+		};
+
+		let rpc = match env.get_string(rpc) {
+			Ok(s) => s,
+			Err(err) => {
+				let _ = env.throw_new("java/lang/Exception", err.to_string());
+				return env.new_string("").expect("Creating an empty string never fails");
+			},
+		};
+
+		// Query the client directly so that the response isn't limited by the size of a buffer.
+		let response = parse_json_query(rpc.as_ptr(), rpc.to_bytes().len())
+			.and_then(|query| client.rpc_query(query, client.rpc_timeout()));
+
+		let response = match response {
+			Ok(response) => response,
+			Err(_) => {
+				let _ = env.throw_new("java/lang/Exception", "failed to perform RPC query");
+				return env.new_string("").expect("Creating an empty string never fails");
+			},
+		};
+
+		match env.new_string(response) {
+			Ok(s) => s,
+			Err(err) => {
+				let _ = env.throw_new("java/lang/Exception", err.to_string());
+				return env.new_string("").expect("Creating an empty string never fails");
+			}
+		}
+	}, || JObject::null().into())
+}
+
+#[cfg(feature = "jni")]
+#[no_mangle]
+pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_subscribe(env: JNIEnv, _: JClass, parity: jlong, query: JString, listener: JObject) -> jlong {
+	jni_catch_unwind(&env, || {
+		let client: &ParityClient = match handle_ref(parity as usize as *const c_void) {
+			Ok(client) => client,
+			Err(_) => {
+				let _ = env.throw_new("java/lang/NullPointerException", "the Parity client is null or invalid");
+				return 0;
+			},
+		};
+
+		let query = match env.get_string(query) {
+			Ok(s) => s,
+			Err(err) => {
+				let _ = env.throw_new("java/lang/Exception", err.to_string());
+				return 0;
+			},
+		};
+
+		let listener = match (env.get_java_vm(), env.new_global_ref(listener)) {
+			(Ok(vm), Ok(listener)) => JavaListener { vm, listener },
+			_ => {
+				let _ = env.throw_new("java/lang/Exception", "failed to reference the listener");
+				return 0;
+			},
+		};
+
+		let sub_id = parse_json_query(query.as_ptr(), query.to_bytes().len())
+			.and_then(|query| client.subscribe(query, move |msg| listener.call(msg)));
+
+		match sub_id {
+			Ok(sub_id) => sub_id as jlong,
+			Err(_) => {
+				let _ = env.throw_new("java/lang/Exception", "failed to subscribe");
+				0
+			},
+		}
+	}, || 0)
+}
+
+#[cfg(feature = "jni")]
+#[no_mangle]
+pub unsafe extern "system" fn Java_io_parity_ethereum_Parity_unsubscribe(_env: JNIEnv, _: JClass, parity: jlong, sub_id: jlong) {
+	let parity = parity as usize as *mut c_void;
+	parity_unsubscribe(parity, sub_id as usize);
+}
+
+// Runs the body of a JNI function. Unwinding into the JVM is undefined behavior, so a panic is
+// turned into a Java exception instead, and the value of `on_panic` is returned.
+#[cfg(feature = "jni")]
+fn jni_catch_unwind<R, F, P>(env: &JNIEnv, f: F, on_panic: P) -> R
+	where F: FnOnce() -> R, P: FnOnce() -> R
+{
+	panic::catch_unwind(panic::AssertUnwindSafe(f)).unwrap_or_else(|_| {
+		let _ = env.throw_new("java/lang/RuntimeException", "Parity panicked");
+		on_panic()
+	})
+}
+
+// Java object whose `onNotification` method receives the messages of a subscription. The global
+// reference to the object is released when the subscription thread ends.
+#[cfg(feature = "jni")]
+struct JavaListener {
+	vm: JavaVM,
+	listener: GlobalRef,
+}
+
+#[cfg(feature = "jni")]
+impl JavaListener {
+	fn call(&self, msg: &str) {
+		// The response to the subscription request is delivered from the Java thread that
+		// subscribed, and the notifications from a native thread that must be attached first.
+		match self.vm.get_env() {
+			Ok(env) => self.call_with_env(&env, msg),
+			Err(_) => {
+				if let Ok(env) = self.vm.attach_current_thread() {
+					self.call_with_env(&env, msg);
+				}
+			},
+		}
+	}
+
+	fn call_with_env(&self, env: &JNIEnv, msg: &str) {
+		let msg = match env.new_string(msg) {
+			Ok(msg) => msg,
+			Err(_) => return,
+		};
+
+		let result = env.call_method(self.listener.as_obj(), "onNotification", "(Ljava/lang/String;)V", &[JValue::Object(msg.into())]);
+		// An exception thrown by the listener must not prevent the next notifications.
+		if result.is_err() || env.exception_check().unwrap_or(false) {
+			let _ = env.exception_clear();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	extern "C" fn count_dropped(custom: *mut c_void, count: usize) {
+		let dropped = unsafe { &*(custom as *const AtomicUsize) };
+		dropped.fetch_add(count, Ordering::SeqCst);
+	}
+
+	fn queue_with(max: usize, policy: BufferPolicy) -> NotificationQueue {
+		let queue = NotificationQueue::new();
+		queue.set_limit(max, policy, None);
+		queue
+	}
+
+	fn pop_notification(queue: &NotificationQueue) -> Option<String> {
+		queue.pop().map(|(notification, _)| notification)
+	}
+
+	#[test]
+	fn notification_queue_blocks_until_there_is_room() {
+		let queue = Arc::new(queue_with(1, BufferPolicy::Block));
+		queue.push("a".into());
+
+		let pushed = Arc::new(AtomicBool::new(false));
+		let pusher = {
+			let queue = queue.clone();
+			let pushed = pushed.clone();
+			thread::spawn(move || {
+				queue.push("b".into());
+				pushed.store(true, Ordering::SeqCst);
+			})
+		};
+		thread::sleep(Duration::from_millis(100));
+		assert!(!pushed.load(Ordering::SeqCst));
+
+		assert_eq!(pop_notification(&queue), Some("a".into()));
+		pusher.join().unwrap();
+		assert!(pushed.load(Ordering::SeqCst));
+		assert_eq!(pop_notification(&queue), Some("b".into()));
+	}
+
+	#[test]
+	fn notification_queue_drops_the_oldest() {
+		let queue = queue_with(2, BufferPolicy::DropOldest);
+		queue.push("a".into());
+		queue.push("b".into());
+		queue.push("c".into());
+		queue.close();
+
+		assert_eq!(pop_notification(&queue), Some("b".into()));
+		assert_eq!(pop_notification(&queue), Some("c".into()));
+		assert_eq!(pop_notification(&queue), None);
+	}
+
+	#[test]
+	fn notification_queue_drops_the_newest() {
+		let queue = queue_with(2, BufferPolicy::DropNewest);
+		queue.push("a".into());
+		queue.push("b".into());
+		queue.push("c".into());
+		queue.close();
+
+		assert_eq!(pop_notification(&queue), Some("a".into()));
+		assert_eq!(pop_notification(&queue), Some("b".into()));
+		assert_eq!(pop_notification(&queue), None);
+	}
+
+	#[test]
+	fn notification_queue_drops_beyond_a_lowered_limit() {
+		let queue = NotificationQueue::new();
+		queue.push("a".into());
+		queue.push("b".into());
+		queue.push("c".into());
+		queue.set_limit(1, BufferPolicy::DropNewest, None);
+		queue.close();
+
+		assert_eq!(pop_notification(&queue), Some("a".into()));
+		assert_eq!(pop_notification(&queue), None);
+	}
+
+	#[test]
+	fn notification_queue_discards_and_unblocks() {
+		let queue = Arc::new(queue_with(1, BufferPolicy::Block));
+		queue.push("a".into());
+
+		let pusher = {
+			let queue = queue.clone();
+			thread::spawn(move || queue.push("b".into()))
+		};
+		thread::sleep(Duration::from_millis(100));
+		queue.discard();
+		pusher.join().unwrap();
+
+		queue.push("c".into());
+		assert_eq!(pop_notification(&queue), None);
+	}
+
+	#[test]
+	fn notification_queue_reports_the_dropped_count() {
+		let dropped = AtomicUsize::new(0);
+		let on_dropped = CallbackDropped(count_dropped, &dropped as *const AtomicUsize as *mut c_void);
+		let queue = NotificationQueue::new();
+		queue.set_limit(1, BufferPolicy::DropOldest, Some(on_dropped));
+		for notification in &["a", "b", "c"] {
+			queue.push(notification.to_string());
+		}
+
+		let (notification, reported) = queue.pop().unwrap();
+		assert_eq!(notification, "c");
+		let (count, on_dropped) = reported.unwrap();
+		assert_eq!(count, 2);
+		on_dropped.call(count);
+		assert_eq!(dropped.load(Ordering::SeqCst), 2);
+
+		// The count starts over after each report.
+		queue.push("d".into());
+		let (notification, reported) = queue.pop().unwrap();
+		assert_eq!(notification, "d");
+		assert!(reported.is_none());
+	}
+
+	lazy_static! {
+		// The address of a destroyed handle can be reused by the next one, so the tests creating
+		// handles don't run concurrently.
+		static ref HANDLES: Mutex<()> = Mutex::new(());
+	}
+
+	#[derive(Debug, PartialEq)]
+	struct Apple(u32);
+	impl HandleType for Apple {
+		const TAG: u32 = 0x7465_6161; // "teaa"
+	}
+
+	#[derive(Debug, PartialEq)]
+	struct Pear(u32);
+	impl HandleType for Pear {
+		const TAG: u32 = 0x7465_7065; // "tepe"
+	}
+
+	#[test]
+	fn handles_give_back_their_object() {
+		let _guard = HANDLES.lock().unwrap();
+		let handle = into_handle(Apple(1));
+		unsafe {
+			assert!(check_handle::<Apple>(handle).is_ok());
+			assert_eq!(handle_ref::<Apple>(handle).unwrap(), &Apple(1));
+			handle_mut::<Apple>(handle).unwrap().0 = 2;
+			assert_eq!(take_handle::<Apple>(handle).unwrap(), Apple(2));
+		}
+	}
+
+	#[test]
+	fn handles_of_another_type_are_rejected() {
+		let _guard = HANDLES.lock().unwrap();
+		let handle = into_handle(Apple(1));
+		unsafe {
+			assert_eq!(check_handle::<Pear>(handle), Err(ParityError::WrongHandleType));
+			assert_eq!(handle_ref::<Pear>(handle).unwrap_err(), ParityError::WrongHandleType);
+			assert_eq!(handle_mut::<Pear>(handle).unwrap_err(), ParityError::WrongHandleType);
+			assert_eq!(take_handle::<Pear>(handle).unwrap_err(), ParityError::WrongHandleType);
+			// The handle is still usable with its own type.
+			assert_eq!(take_handle::<Apple>(handle).unwrap(), Apple(1));
+		}
+	}
+
+	#[test]
+	fn null_handles_are_rejected() {
+		unsafe {
+			assert_eq!(check_handle::<Apple>(ptr::null()), Err(ParityError::NullPointer));
+			assert_eq!(handle_ref::<Apple>(ptr::null()).unwrap_err(), ParityError::NullPointer);
+			assert_eq!(handle_mut::<Apple>(ptr::null_mut()).unwrap_err(), ParityError::NullPointer);
+			assert_eq!(take_handle::<Apple>(ptr::null_mut()).unwrap_err(), ParityError::NullPointer);
+		}
+	}
+
+	#[test]
+	fn destroyed_handles_are_rejected() {
+		let _guard = HANDLES.lock().unwrap();
+		let handle = into_handle(Apple(1));
+		unsafe {
+			assert!(take_handle::<Apple>(handle).is_ok());
+			assert_eq!(take_handle::<Apple>(handle).unwrap_err(), ParityError::WrongHandleType);
+			assert_eq!(check_handle::<Apple>(handle), Err(ParityError::WrongHandleType));
+			assert_eq!(handle_ref::<Apple>(handle).unwrap_err(), ParityError::WrongHandleType);
+			assert_eq!(handle_mut::<Apple>(handle).unwrap_err(), ParityError::WrongHandleType);
+		}
+	}
+
+	#[test]
+	fn query_method_reads_the_method() {
+		assert_eq!(query_method(r#"{"method":"eth_blockNumber","params":[],"id":1,"jsonrpc":"2.0"}"#), Some("eth_blockNumber"));
+		assert_eq!(query_method(r#"{"jsonrpc": "2.0", "method" : "eth_subscribe", "params": ["newHeads"]}"#), Some("eth_subscribe"));
+	}
+
+	#[test]
+	fn query_method_reads_the_first_method_of_a_batch() {
+		let query = r#"[{"method":"eth_blockNumber","id":1},{"method":"net_version","id":2}]"#;
+		assert_eq!(query_method(query), Some("eth_blockNumber"));
+	}
+
+	#[test]
+	fn query_method_rejects_malformed_methods() {
+		assert_eq!(query_method(r#"{"method"}"#), None);
+		assert_eq!(query_method(r#"{"method":1}"#), None);
+		assert_eq!(query_method(r#"{"method":""}"#), None);
+		assert_eq!(query_method(r#"{"method":"eth_block"#), None);
+		assert_eq!(query_method(r#"{"method":"eth block/number"}"#), None);
+		assert_eq!(query_method("not json"), None);
+	}
+
+	#[test]
+	fn query_method_without_method() {
+		assert_eq!(query_method(r#"{"params":[],"id":1,"jsonrpc":"2.0"}"#), None);
+		assert_eq!(query_method(""), None);
+	}
+
+	#[test]
+	fn wait_for_returns_the_outcome_of_the_future() {
+		assert_eq!(wait_for(future::ok::<_, ()>(1), Duration::from_secs(1)), Ok(1));
+		assert_eq!(wait_for(future::err::<u32, _>(()), Duration::from_secs(1)), Err(ParityError::RpcFailed));
+	}
+
+	#[test]
+	fn wait_for_wakes_up_when_notified() {
+		let (tx, rx) = oneshot::channel();
+		let sender = thread::spawn(move || {
+			thread::sleep(Duration::from_millis(50));
+			tx.send(1).unwrap();
+		});
+		assert_eq!(wait_for(rx, Duration::from_secs(10)), Ok(1));
+		sender.join().unwrap();
+	}
+
+	#[test]
+	fn wait_for_times_out() {
+		assert_eq!(wait_for(future::empty::<u32, ()>(), Duration::from_millis(50)), Err(ParityError::Timeout));
+		assert_eq!(last_error(), "The request timed out");
+	}
+
+	fn last_error() -> String {
+		LAST_ERROR.with(|last_error| match *last_error.borrow() {
+			Some(ref message) => message.to_string_lossy().into_owned(),
+			None => String::new(),
+		})
+	}
+
+	// Parses the arguments, which must fail, and returns the error code and message.
+	fn config_error(args: &[&str]) -> (c_int, String) {
+		let mut output = ptr::null_mut();
+		let code = unsafe { config_from_args(args, &mut output) };
+		assert!(output.is_null());
+		(code, last_error())
+	}
+
+	#[test]
+	fn clap_error_message_keeps_the_first_line_without_colors() {
+		let message = "\u{1b}[1;31merror:\u{1b}[0m Found argument '\u{1b}[33m--foo\u{1b}[0m' which wasn't expected\n\nUSAGE:\n    parity [OPTIONS]";
+		assert_eq!(clap_error_message(message), "error: Found argument '--foo' which wasn't expected");
+		assert_eq!(clap_error_message(""), "");
+	}
+
+	#[test]
+	fn config_errors_of_clap() {
+		let (code, message) = config_error(&["parity", "--foo"]);
+		assert_eq!(code, ParityError::CliParse as c_int);
+		assert!(message.contains("--foo"), "{}", message);
+		assert!(!message.contains('\n') && !message.contains('\u{1b}'), "{:?}", message);
+	}
+
+	#[test]
+	fn config_errors_of_the_peer_configuration() {
+		let (code, message) = config_error(&["parity", "--max-peers=5", "--min-peers=10"]);
+		assert_eq!(code, ParityError::CliParse as c_int);
+		assert_eq!(message, "You have supplied `min_peers` > `max_peers`");
+	}
+
+	#[test]
+	fn config_errors_of_a_missing_config_file() {
+		let dir = TempDir::new("parity-clib").unwrap();
+		let path = dir.path().join("missing.toml");
+		let (code, message) = config_error(&["parity", "--config", path.to_str().unwrap()]);
+		assert_eq!(code, ParityError::ConfigRead as c_int);
+		assert!(message.starts_with("Error reading config file at"), "{}", message);
+	}
+
+	#[test]
+	fn config_errors_of_an_invalid_config_file() {
+		let dir = TempDir::new("parity-clib").unwrap();
+		let path = dir.path().join("config.toml");
+		::std::fs::write(&path, "[parity]\nmode = 1\n").unwrap();
+		let (code, message) = config_error(&["parity", "--config", path.to_str().unwrap()]);
+		assert_eq!(code, ParityError::ConfigParse as c_int);
+		assert!(message.starts_with("Invalid parameters in config file"), "{}", message);
 	}
 }
@@ -0,0 +1,313 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+// Node.js bindings, built on N-API so that the same library works with every Node.js version
+// from 10.17 on. The library is loaded as a native addon, for example with
+// `process.dlopen(module, "libparity.so")`, and exports:
+//
+// - `configFromCli(args)`: parses an array of CLI arguments into a configuration object.
+// - `build(config)`: starts a client from a configuration object, which is consumed.
+// - `rpc(client, query)`: performs an RPC query and returns a `Promise` of the JSON response.
+// - `destroy(client)`: shuts the client down.
+//
+// The configuration and client objects are opaque handles. The N-API functions are provided by
+// the Node.js executable, so the library is linked without them.
+
+use std::mem;
+use std::os::raw::{c_char, c_void};
+use std::ptr;
+
+use super::{handle_ref, parity_config_from_cli, parity_destroy, parity_start, ParityClient, ParityError, ParityParams};
+
+#[allow(non_camel_case_types)]
+mod sys {
+	use std::os::raw::{c_char, c_int, c_void};
+
+	pub enum napi_env__ {}
+	pub enum napi_value__ {}
+	pub enum napi_callback_info__ {}
+	pub enum napi_deferred__ {}
+	pub enum napi_threadsafe_function__ {}
+
+	pub type napi_env = *mut napi_env__;
+	pub type napi_value = *mut napi_value__;
+	pub type napi_callback_info = *mut napi_callback_info__;
+	pub type napi_deferred = *mut napi_deferred__;
+	pub type napi_threadsafe_function = *mut napi_threadsafe_function__;
+	pub type napi_status = c_int;
+
+	pub type napi_callback = Option<unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value>;
+	pub type napi_finalize = Option<unsafe extern "C" fn(napi_env, *mut c_void, *mut c_void)>;
+	pub type napi_threadsafe_function_call_js = Option<unsafe extern "C" fn(napi_env, napi_value, *mut c_void, *mut c_void)>;
+
+	pub const NAPI_OK: napi_status = 0;
+	pub const NAPI_AUTO_LENGTH: usize = !0;
+	pub const NAPI_TSFN_RELEASE: c_int = 0;
+	pub const NAPI_TSFN_BLOCKING: c_int = 1;
+
+	extern "C" {
+		pub fn napi_get_cb_info(env: napi_env, info: napi_callback_info, argc: *mut usize, argv: *mut napi_value, this_arg: *mut napi_value, data: *mut *mut c_void) -> napi_status;
+		pub fn napi_create_function(env: napi_env, name: *const c_char, length: usize, cb: napi_callback, data: *mut c_void, result: *mut napi_value) -> napi_status;
+		pub fn napi_set_named_property(env: napi_env, object: napi_value, name: *const c_char, value: napi_value) -> napi_status;
+		pub fn napi_get_undefined(env: napi_env, result: *mut napi_value) -> napi_status;
+		pub fn napi_get_array_length(env: napi_env, value: napi_value, result: *mut u32) -> napi_status;
+		pub fn napi_get_element(env: napi_env, object: napi_value, index: u32, result: *mut napi_value) -> napi_status;
+		pub fn napi_get_value_string_utf8(env: napi_env, value: napi_value, buf: *mut c_char, bufsize: usize, result: *mut usize) -> napi_status;
+		pub fn napi_create_string_utf8(env: napi_env, string: *const c_char, length: usize, result: *mut napi_value) -> napi_status;
+		pub fn napi_create_external(env: napi_env, data: *mut c_void, finalize_cb: napi_finalize, finalize_hint: *mut c_void, result: *mut napi_value) -> napi_status;
+		pub fn napi_get_value_external(env: napi_env, value: napi_value, result: *mut *mut c_void) -> napi_status;
+		pub fn napi_throw_error(env: napi_env, code: *const c_char, msg: *const c_char) -> napi_status;
+		pub fn napi_create_error(env: napi_env, code: napi_value, msg: napi_value, result: *mut napi_value) -> napi_status;
+		pub fn napi_create_promise(env: napi_env, deferred: *mut napi_deferred, promise: *mut napi_value) -> napi_status;
+		pub fn napi_resolve_deferred(env: napi_env, deferred: napi_deferred, resolution: napi_value) -> napi_status;
+		pub fn napi_reject_deferred(env: napi_env, deferred: napi_deferred, rejection: napi_value) -> napi_status;
+		pub fn napi_create_threadsafe_function(env: napi_env, func: napi_value, async_resource: napi_value, async_resource_name: napi_value, max_queue_size: usize, initial_thread_count: usize, thread_finalize_data: *mut c_void, thread_finalize_cb: napi_finalize, context: *mut c_void, call_js_cb: napi_threadsafe_function_call_js, result: *mut napi_threadsafe_function) -> napi_status;
+		pub fn napi_call_threadsafe_function(func: napi_threadsafe_function, data: *mut c_void, is_blocking: c_int) -> napi_status;
+		pub fn napi_release_threadsafe_function(func: napi_threadsafe_function, mode: c_int) -> napi_status;
+	}
+}
+
+use self::sys::*;
+
+#[no_mangle]
+pub unsafe extern "C" fn napi_register_module_v1(env: napi_env, exports: napi_value) -> napi_value {
+	let functions: [(&[u8], unsafe extern "C" fn(napi_env, napi_callback_info) -> napi_value); 4] = [
+		(b"configFromCli\0", config_from_cli),
+		(b"build\0", build),
+		(b"rpc\0", rpc),
+		(b"destroy\0", destroy),
+	];
+
+	for &(name, function) in functions.iter() {
+		let name = name.as_ptr() as *const c_char;
+		let mut value = ptr::null_mut();
+		if napi_create_function(env, name, NAPI_AUTO_LENGTH, Some(function), ptr::null_mut(), &mut value) != NAPI_OK
+			|| napi_set_named_property(env, exports, name, value) != NAPI_OK
+		{
+			throw(env, "failed to register the Parity functions");
+			break;
+		}
+	}
+
+	exports
+}
+
+unsafe extern "C" fn config_from_cli(env: napi_env, info: napi_callback_info) -> napi_value {
+	let args = match arguments(env, info, 1) {
+		Some(args) => args[0],
+		None => return throw(env, "expected the array of CLI arguments"),
+	};
+
+	let mut len = 0;
+	if napi_get_array_length(env, args, &mut len) != NAPI_OK {
+		return throw(env, "expected the array of CLI arguments");
+	}
+
+	let mut opts = Vec::with_capacity(len as usize);
+	for n in 0 .. len {
+		let mut elem = ptr::null_mut();
+		if napi_get_element(env, args, n, &mut elem) != NAPI_OK {
+			return throw(env, "failed to read the CLI arguments");
+		}
+		match string(env, elem) {
+			Some(opt) => opts.push(opt),
+			None => return throw(env, "the CLI arguments must be strings"),
+		}
+	}
+
+	let opts_ptrs = opts.iter().map(|opt| opt.as_ptr() as *const c_char).collect::<Vec<_>>();
+	let opts_lens = opts.iter().map(|opt| opt.len()).collect::<Vec<_>>();
+
+	let mut out = ptr::null_mut();
+	match parity_config_from_cli(opts_ptrs.as_ptr(), opts_lens.as_ptr(), opts.len(), &mut out) {
+		0 => external(env, out),
+		_ => throw(env, "failed to create config object"),
+	}
+}
+
+unsafe extern "C" fn build(env: napi_env, info: napi_callback_info) -> napi_value {
+	let config = match arguments(env, info, 1).and_then(|args| handle(env, args[0])) {
+		Some(config) => config,
+		None => return throw(env, "expected a config object"),
+	};
+
+	let params = ParityParams {
+		configuration: config,
+		.. mem::zeroed()
+	};
+
+	let mut out = ptr::null_mut();
+	match parity_start(&params, &mut out) {
+		0 => external(env, out),
+		_ => throw(env, "failed to start Parity"),
+	}
+}
+
+unsafe extern "C" fn destroy(env: napi_env, info: napi_callback_info) -> napi_value {
+	if let Some(client) = arguments(env, info, 1).and_then(|args| handle(env, args[0])) {
+		parity_destroy(client);
+	}
+	undefined(env)
+}
+
+unsafe extern "C" fn rpc(env: napi_env, info: napi_callback_info) -> napi_value {
+	let (client, query) = match arguments(env, info, 2) {
+		Some(args) => (handle(env, args[0]), string(env, args[1])),
+		None => return throw(env, "expected a client and a query"),
+	};
+	let client = match client {
+		Some(client) => match handle_ref::<ParityClient>(client) {
+			Ok(client) => client,
+			Err(_) => return throw(env, "expected a client"),
+		},
+		None => return throw(env, "expected a client"),
+	};
+	let query = match query {
+		Some(query) => query,
+		None => return throw(env, "the query must be a string"),
+	};
+
+	let mut deferred = ptr::null_mut();
+	let mut promise = ptr::null_mut();
+	if napi_create_promise(env, &mut deferred, &mut promise) != NAPI_OK {
+		return throw(env, "failed to create a promise");
+	}
+
+	// The promise can only be settled from the JavaScript thread, where the thread-safe function
+	// forwards the outcome of the query.
+	let mut resource_name = ptr::null_mut();
+	let mut tsfn = ptr::null_mut();
+	if napi_create_string_utf8(env, b"parity-rpc\0".as_ptr() as *const c_char, NAPI_AUTO_LENGTH, &mut resource_name) != NAPI_OK
+		|| napi_create_threadsafe_function(env, ptr::null_mut(), ptr::null_mut(), resource_name, 0, 1, ptr::null_mut(), None, deferred as *mut c_void, Some(settle_rpc), &mut tsfn) != NAPI_OK
+	{
+		return throw(env, "failed to create a promise");
+	}
+
+	let pending = PendingRpc { tsfn, result: Err(ParityError::RpcFailed) };
+	client.spawn_query(&query, client.rpc_timeout(), move |result| {
+		let mut pending = pending;
+		pending.result = result;
+	});
+
+	promise
+}
+
+// Query started by `rpc`, whose promise is settled with `result` when dropped. A query that is
+// dropped without completing, for example because the client shut down, rejects its promise.
+struct PendingRpc {
+	tsfn: napi_threadsafe_function,
+	result: Result<String, ParityError>,
+}
+
+// Thread-safe functions are meant to be called from any thread.
+unsafe impl Send for PendingRpc {}
+
+impl Drop for PendingRpc {
+	fn drop(&mut self) {
+		let result = mem::replace(&mut self.result, Err(ParityError::RpcFailed));
+		let result = Box::into_raw(Box::new(result)) as *mut c_void;
+		unsafe {
+			if napi_call_threadsafe_function(self.tsfn, result, NAPI_TSFN_BLOCKING) != NAPI_OK {
+				drop(Box::from_raw(result as *mut Result<String, ParityError>));
+			}
+			napi_release_threadsafe_function(self.tsfn, NAPI_TSFN_RELEASE);
+		}
+	}
+}
+
+// Settles the promise of an RPC query with its outcome, on the JavaScript thread.
+unsafe extern "C" fn settle_rpc(env: napi_env, _: napi_value, deferred: *mut c_void, result: *mut c_void) {
+	let result = Box::from_raw(result as *mut Result<String, ParityError>);
+	// The environment is gone if Node.js is shutting down.
+	if env.is_null() {
+		return;
+	}
+
+	let deferred = deferred as napi_deferred;
+	match *result {
+		Ok(response) => {
+			let mut value = ptr::null_mut();
+			napi_create_string_utf8(env, response.as_ptr() as *const c_char, response.len(), &mut value);
+			napi_resolve_deferred(env, deferred, value);
+		},
+		Err(err) => {
+			let message = match err {
+				ParityError::Timeout => "the RPC query timed out",
+				_ => "failed to perform RPC query",
+			};
+			let mut msg = ptr::null_mut();
+			let mut error = ptr::null_mut();
+			napi_create_string_utf8(env, message.as_ptr() as *const c_char, message.len(), &mut msg);
+			napi_create_error(env, ptr::null_mut(), msg, &mut error);
+			napi_reject_deferred(env, deferred, error);
+		},
+	}
+}
+
+// Reads the first `count` arguments of a call, or returns `None` if there are fewer.
+unsafe fn arguments(env: napi_env, info: napi_callback_info, count: usize) -> Option<Vec<napi_value>> {
+	let mut args = vec![ptr::null_mut(); count];
+	let mut argc = count;
+	if napi_get_cb_info(env, info, &mut argc, args.as_mut_ptr(), ptr::null_mut(), ptr::null_mut()) != NAPI_OK || argc < count {
+		return None;
+	}
+	Some(args)
+}
+
+unsafe fn string(env: napi_env, value: napi_value) -> Option<String> {
+	let mut len = 0;
+	if napi_get_value_string_utf8(env, value, ptr::null_mut(), 0, &mut len) != NAPI_OK {
+		return None;
+	}
+
+	// N-API always writes a null terminator.
+	let mut buf = vec![0u8; len + 1];
+	if napi_get_value_string_utf8(env, value, buf.as_mut_ptr() as *mut c_char, buf.len(), &mut len) != NAPI_OK {
+		return None;
+	}
+	buf.truncate(len);
+	String::from_utf8(buf).ok()
+}
+
+unsafe fn external(env: napi_env, data: *mut c_void) -> napi_value {
+	let mut value = ptr::null_mut();
+	match napi_create_external(env, data, None, ptr::null_mut(), &mut value) {
+		NAPI_OK => value,
+		_ => throw(env, "failed to create a handle"),
+	}
+}
+
+unsafe fn handle(env: napi_env, value: napi_value) -> Option<*mut c_void> {
+	let mut data = ptr::null_mut();
+	match napi_get_value_external(env, value, &mut data) {
+		NAPI_OK => Some(data),
+		_ => None,
+	}
+}
+
+unsafe fn undefined(env: napi_env) -> napi_value {
+	let mut value = ptr::null_mut();
+	napi_get_undefined(env, &mut value);
+	value
+}
+
+// Throws a JavaScript `Error`. The returned value is ignored by Node.js while the exception is
+// pending.
+unsafe fn throw(env: napi_env, message: &str) -> napi_value {
+	let message = ::std::ffi::CString::new(message).expect("error messages have no null bytes; qed");
+	napi_throw_error(env, ptr::null(), message.as_ptr());
+	undefined(env)
+}
@@ -0,0 +1,147 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+// Starts a dev chain client through P/Invoke and performs a synchronous RPC request.
+//
+// Run with the directory containing the parity library in the library search path, for example
+// `LD_LIBRARY_PATH=../../../target/debug dotnet run`.
+
+using System;
+using System.IO;
+using System.Runtime.InteropServices;
+using System.Text;
+
+namespace ParityInterop
+{
+	[UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+	delegate void RestartCallback(IntPtr custom, IntPtr newChain, UIntPtr newChainLen);
+
+	[StructLayout(LayoutKind.Sequential)]
+	struct ParityParams
+	{
+		public IntPtr configuration;
+		public RestartCallback onClientRestartCb;
+		public IntPtr onClientRestartCbCustom;
+	}
+
+	// `parity_destroy` tolerates clients that were already destroyed, as `SafeHandle` expects.
+	class ParityClientHandle : SafeHandle
+	{
+		public ParityClientHandle() : base(IntPtr.Zero, true) {}
+
+		public override bool IsInvalid => handle == IntPtr.Zero;
+
+		protected override bool ReleaseHandle()
+		{
+			Native.parity_destroy(handle);
+			return true;
+		}
+	}
+
+	static class Native
+	{
+		const string Lib = "parity";
+
+		[DllImport(Lib)]
+		public static extern int parity_config_from_cli([MarshalAs(UnmanagedType.LPArray, ArraySubType = UnmanagedType.LPStr)] string[] args, UIntPtr[] argsLens, UIntPtr len, out IntPtr output);
+
+		[DllImport(Lib)]
+		public static extern int parity_start(ref ParityParams parameters, out ParityClientHandle output);
+
+		[DllImport(Lib)]
+		public static extern void parity_destroy(IntPtr client);
+
+		[DllImport(Lib)]
+		public static extern int parity_client_is_valid(IntPtr client);
+
+		[DllImport(Lib)]
+		public static extern int parity_rpc_sync(ParityClientHandle client, [MarshalAs(UnmanagedType.LPStr)] string query, UIntPtr len, UIntPtr timeoutMs, StringBuilder outBuf, UIntPtr outBufLen, out UIntPtr outWritten);
+	}
+
+	class Program
+	{
+		static int Main()
+		{
+			var basePath = Path.Combine(Path.GetTempPath(), Path.GetRandomFileName());
+			var args = new[] { "--chain", "dev", "--mode", "offline", "--no-ipc", "--no-ws", "--no-jsonrpc", "--base-path", basePath };
+			var argsLens = Array.ConvertAll(args, arg => (UIntPtr)Encoding.UTF8.GetByteCount(arg));
+
+			RestartCallback onRestart = (custom, newChain, newChainLen) => {};
+			var parameters = new ParityParams { onClientRestartCb = onRestart };
+			if (Native.parity_config_from_cli(args, argsLens, (UIntPtr)args.Length, out parameters.configuration) != 0)
+			{
+				return Fail("parity_config_from_cli failed");
+			}
+
+			IntPtr raw;
+			using (var client = StartClient(ref parameters))
+			{
+				if (client.IsInvalid)
+				{
+					return Fail("parity_start failed");
+				}
+				raw = client.DangerousGetHandle();
+
+				var query = "{\"method\":\"web3_clientVersion\",\"params\":[],\"id\":1,\"jsonrpc\":\"2.0\"}";
+				// Parity doesn't write any null terminator, so the buffer starts zeroed and one
+				// character is kept for the terminator.
+				var buf = new StringBuilder(new string('\0', 4096));
+				UIntPtr written;
+				var code = Native.parity_rpc_sync(client, query, (UIntPtr)query.Length, UIntPtr.Zero, buf, (UIntPtr)(buf.Length - 1), out written);
+				if (code != 0)
+				{
+					return Fail($"parity_rpc_sync returned {code}");
+				}
+
+				var response = buf.ToString(0, (int)written);
+				if (!response.Contains("Parity"))
+				{
+					return Fail($"unexpected response {response}");
+				}
+				Console.WriteLine(response);
+
+				if (Native.parity_client_is_valid(raw) != 1)
+				{
+					return Fail("running client reported as invalid");
+				}
+			}
+
+			// The handle destroyed the client: it isn't valid anymore, and destroying it again is harmless.
+			if (Native.parity_client_is_valid(raw) != 0)
+			{
+				return Fail("destroyed client reported as valid");
+			}
+			Native.parity_destroy(raw);
+
+			GC.KeepAlive(onRestart);
+			Directory.Delete(basePath, true);
+			return 0;
+		}
+
+		static ParityClientHandle StartClient(ref ParityParams parameters)
+		{
+			ParityClientHandle client;
+			Native.parity_start(ref parameters, out client);
+			return client;
+		}
+
+		static int Fail(string message)
+		{
+			Console.Error.WriteLine(message);
+			return 1;
+		}
+	}
+}
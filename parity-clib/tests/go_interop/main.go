@@ -0,0 +1,98 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+// Starts a dev chain client through cgo and performs a synchronous RPC request with a buffer
+// allocated by Go. Run through `run.sh`.
+package main
+
+/*
+#cgo CFLAGS: -I${SRCDIR}/../..
+#cgo LDFLAGS: -lparity
+#include <stdlib.h>
+#include <parity.h>
+
+static void on_restart(void* custom, const char* new_chain, size_t new_chain_len) {}
+
+static void set_restart_callback(struct ParityParams* params) {
+	params->on_client_restart_cb = on_restart;
+}
+*/
+import "C"
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"os"
+	"strings"
+	"unsafe"
+)
+
+func main() {
+	basePath, err := ioutil.TempDir("", "parity-go-interop")
+	if err != nil {
+		fail("creating the base path: %v", err)
+	}
+	defer os.RemoveAll(basePath)
+
+	args := []string{"--chain", "dev", "--mode", "offline", "--no-ipc", "--no-ws", "--no-jsonrpc", "--base-path", basePath}
+	argPtrs := make([]*C.char, len(args))
+	argLens := make([]C.size_t, len(args))
+	for i, arg := range args {
+		argPtrs[i] = C.CString(arg)
+		defer C.free(unsafe.Pointer(argPtrs[i]))
+		argLens[i] = C.size_t(len(arg))
+	}
+
+	var params C.struct_ParityParams
+	C.set_restart_callback(&params)
+	if code := C.parity_config_from_cli(&argPtrs[0], &argLens[0], C.size_t(len(args)), &params.configuration); code != 0 {
+		fail("parity_config_from_cli returned %d", code)
+	}
+
+	var client unsafe.Pointer
+	if code := C.parity_start(&params, &client); code != 0 || client == nil {
+		fail("parity_start returned %d", code)
+	}
+	defer C.parity_destroy(client)
+
+	query := C.CString(`{"method":"web3_clientVersion","params":[],"id":1,"jsonrpc":"2.0"}`)
+	defer C.free(unsafe.Pointer(query))
+
+	// The output buffer belongs to Go: no call to `parity_string_destroy` is needed.
+	buf := make([]byte, 4096)
+	var written C.size_t
+	code := C.parity_rpc_sync(client, query, C.strlen(query), 0, (*C.char)(unsafe.Pointer(&buf[0])), C.size_t(len(buf)), &written)
+	if code != 0 {
+		fail("parity_rpc_sync returned %d", code)
+	}
+
+	var response struct {
+		Result string `json:"result"`
+	}
+	if err := json.Unmarshal(buf[:written], &response); err != nil {
+		fail("decoding the response: %v", err)
+	}
+	if !strings.Contains(response.Result, "Parity") {
+		fail("unexpected client version %q", response.Result)
+	}
+	fmt.Println("client version:", response.Result)
+}
+
+func fail(format string, args ...interface{}) {
+	fmt.Fprintf(os.Stderr, format+"\n", args...)
+	os.Exit(1)
+}
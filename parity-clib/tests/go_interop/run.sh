@@ -0,0 +1,11 @@
+#!/usr/bin/env sh
+# Builds and runs the Go interop example against the parity library found in the directory
+# passed as first argument, such as `target/debug`.
+
+set -e
+
+LIB_DIR=$(cd "$1" && pwd)
+SRC_DIR=$(cd "$(dirname "$0")" && pwd)
+
+cd "$SRC_DIR"
+CGO_LDFLAGS="-L$LIB_DIR" LD_LIBRARY_PATH="$LIB_DIR" DYLD_LIBRARY_PATH="$LIB_DIR" go run main.go
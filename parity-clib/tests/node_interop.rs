@@ -0,0 +1,35 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "napi")]
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+// Needs Node.js, so it only runs when asked for with `--ignored`, as the CI does.
+#[test]
+#[ignore]
+fn node_interop() {
+	// The test binary lives in `target/<profile>/deps`, next to which cargo put the library.
+	let exe = env::current_exe().unwrap();
+	let lib_dir = exe.parent().and_then(Path::parent).unwrap();
+	let lib_name = format!("{}parity{}", env::consts::DLL_PREFIX, env::consts::DLL_SUFFIX);
+	let script = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/node_interop/test.js");
+
+	let status = Command::new("node").arg(script).arg(lib_dir.join(lib_name)).status().unwrap();
+	assert!(status.success());
+}
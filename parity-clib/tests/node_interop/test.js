@@ -0,0 +1,44 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+// Loads the parity library passed as first argument as a Node.js addon, starts a dev chain client
+// and performs an RPC request through the returned promise.
+
+'use strict';
+
+const assert = require('assert');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+
+const parity = { exports: {} };
+process.dlopen(parity, path.resolve(process.argv[2]));
+const { configFromCli, build, rpc, destroy } = parity.exports;
+
+const basePath = fs.mkdtempSync(path.join(os.tmpdir(), 'parity-'));
+const config = configFromCli(['--chain', 'dev', '--mode', 'offline', '--no-ipc', '--no-ws', '--no-jsonrpc', '--base-path', basePath]);
+const client = build(config);
+
+rpc(client, '{"method":"web3_clientVersion","params":[],"id":1,"jsonrpc":"2.0"}')
+	.then((response) => {
+		assert(JSON.parse(response).result.includes('Parity'), `unexpected response ${response}`);
+		console.log(response);
+	})
+	.catch((err) => {
+		console.error(err);
+		process.exitCode = 1;
+	})
+	.then(() => destroy(client));
@@ -0,0 +1,214 @@
+# Copyright 2015-2018 Parity Technologies (UK) Ltd.
+# This file is part of Parity.
+
+# Parity is free software: you can redistribute it and/or modify
+# it under the terms of the GNU General Public License as published by
+# the Free Software Foundation, either version 3 of the License, or
+# (at your option) any later version.
+
+# Parity is distributed in the hope that it will be useful,
+# but WITHOUT ANY WARRANTY; without even the implied warranty of
+# MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+# GNU General Public License for more details.
+
+# You should have received a copy of the GNU General Public License
+# along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+"""Loads the parity cdylib through ctypes and performs a few RPC requests.
+
+Build the library first with `cargo build -p parity-clib`, then run
+`python3 parity-clib/tests/test_rpc.py`. Set `PARITY_CLIB` to use a library at another path.
+"""
+
+import ctypes
+import json
+import os
+import shutil
+import sys
+import tempfile
+import threading
+import unittest
+
+ROOT = os.path.dirname(os.path.dirname(os.path.dirname(os.path.abspath(__file__))))
+LIB_NAME = {"darwin": "libparity.dylib", "win32": "parity.dll"}.get(sys.platform, "libparity.so")
+LIB_PATH = os.environ.get("PARITY_CLIB", os.path.join(ROOT, "target", "debug", LIB_NAME))
+
+PARITY_OK = 0
+PARITY_ERROR_BUFFER_TOO_SMALL = 6
+
+RESTART_CALLBACK = ctypes.CFUNCTYPE(None, ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t)
+RESPONSE_CALLBACK = ctypes.CFUNCTYPE(None, ctypes.c_void_p, ctypes.c_void_p, ctypes.c_size_t)
+
+
+class ParityParams(ctypes.Structure):
+	_fields_ = [
+		("configuration", ctypes.c_void_p),
+		("on_client_restart_cb", RESTART_CALLBACK),
+		("on_client_restart_cb_custom", ctypes.c_void_p),
+	]
+
+
+def load_library():
+	lib = ctypes.CDLL(LIB_PATH)
+	size_p = ctypes.POINTER(ctypes.c_size_t)
+	lib.parity_config_from_cli.argtypes = [ctypes.POINTER(ctypes.c_char_p), size_p, ctypes.c_size_t, ctypes.POINTER(ctypes.c_void_p)]
+	lib.parity_start.argtypes = [ctypes.POINTER(ParityParams), ctypes.POINTER(ctypes.c_void_p)]
+	lib.parity_destroy.argtypes = [ctypes.c_void_p]
+	lib.parity_rpc.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t, ctypes.c_char_p, size_p]
+	lib.parity_rpc_sync.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t, ctypes.c_size_t, ctypes.c_char_p, ctypes.c_size_t, size_p]
+	lib.parity_rpc_cancelable.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t, RESPONSE_CALLBACK, ctypes.c_void_p, ctypes.POINTER(ctypes.c_void_p)]
+	lib.parity_rpc_abort.argtypes = [ctypes.c_void_p]
+	return lib
+
+
+# The callback must outlive the clients.
+ON_RESTART = RESTART_CALLBACK(lambda custom, chain, chain_len: None)
+
+
+def start_client(lib, base_path, port=30303):
+	args = [b"--chain", b"dev", b"--mode", b"offline", b"--no-ipc", b"--no-ws", b"--no-jsonrpc",
+		b"--no-discovery", b"--port", str(port).encode(), b"--base-path", base_path.encode()]
+	arg_ptrs = (ctypes.c_char_p * len(args))(*args)
+	arg_lens = (ctypes.c_size_t * len(args))(*[len(a) for a in args])
+
+	params = ParityParams(None, ON_RESTART, None)
+	config = ctypes.c_void_p()
+	if lib.parity_config_from_cli(arg_ptrs, arg_lens, len(args), ctypes.byref(config)) != PARITY_OK:
+		raise RuntimeError("parity_config_from_cli failed")
+	params.configuration = config
+
+	client = ctypes.c_void_p()
+	if lib.parity_start(ctypes.byref(params), ctypes.byref(client)) != PARITY_OK or not client:
+		raise RuntimeError("parity_start failed")
+	return client
+
+
+def rpc_sync(lib, client, request, buf_len=4096):
+	buf = ctypes.create_string_buffer(buf_len)
+	written = ctypes.c_size_t()
+	code = lib.parity_rpc_sync(client, request, len(request), 0, buf, buf_len, ctypes.byref(written))
+	return code, buf.raw[:written.value] if code == PARITY_OK else written.value
+
+
+class CancelableRequest(object):
+	"""Request made with `parity_rpc_cancelable`, which `wait` blocks on until it completes or
+	`cancel` aborts it from another thread."""
+
+	def __init__(self, lib, client, request):
+		self.lib = lib
+		self.done = threading.Event()
+		self.response = None
+		self.cancelled = False
+		# Kept alive until the token is destroyed, since the callback may be called until then.
+		self.callback = RESPONSE_CALLBACK(self.on_response)
+		self.token = ctypes.c_void_p()
+		code = lib.parity_rpc_cancelable(client, request, len(request), self.callback, None, ctypes.byref(self.token))
+		if code != PARITY_OK:
+			raise RuntimeError("parity_rpc_cancelable failed")
+
+	def on_response(self, custom, response, response_len):
+		if response:
+			self.response = ctypes.string_at(response, response_len)
+		self.done.set()
+
+	def cancel(self):
+		self.cancelled = True
+		self.done.set()
+
+	def wait(self, timeout=None):
+		"""Returns the response, or None if the request was cancelled or timed out."""
+		self.done.wait(timeout)
+		# The token is destroyed in any case, which aborts the request if it's still running.
+		self.lib.parity_rpc_abort(self.token)
+		return None if self.cancelled else self.response
+
+
+class RpcTest(unittest.TestCase):
+	def setUp(self):
+		self.lib = load_library()
+		self.base_path = tempfile.mkdtemp()
+		self.client = start_client(self.lib, self.base_path)
+
+	def tearDown(self):
+		self.lib.parity_destroy(self.client)
+		shutil.rmtree(self.base_path, ignore_errors=True)
+
+	def rpc_sync(self, request, buf_len=4096):
+		return rpc_sync(self.lib, self.client, request, buf_len)
+
+	def test_client_version(self):
+		request = b'{"method":"web3_clientVersion","params":[],"id":1,"jsonrpc":"2.0"}'
+		code, response = self.rpc_sync(request)
+		self.assertEqual(code, PARITY_OK)
+		self.assertIn("Parity", json.loads(response.decode())["result"])
+
+	def test_buffer_too_small(self):
+		request = b'{"method":"web3_clientVersion","params":[],"id":1,"jsonrpc":"2.0"}'
+		code, needed = self.rpc_sync(request, buf_len=1)
+		self.assertEqual(code, PARITY_ERROR_BUFFER_TOO_SMALL)
+		self.assertGreater(needed, 1)
+
+	def test_concurrent_queries(self):
+		# ctypes releases the GIL during the calls, so the queries really run concurrently.
+		request = b'{"method":"eth_blockNumber","params":[],"id":1,"jsonrpc":"2.0"}'
+		failures = []
+
+		def run_queries():
+			for _ in range(50):
+				buf = ctypes.create_string_buffer(4096)
+				out_len = ctypes.c_size_t(len(buf))
+				code = self.lib.parity_rpc(self.client, request, len(request), buf, ctypes.byref(out_len))
+				if code != PARITY_OK or json.loads(buf.raw[:out_len.value].decode())["result"] != "0x0":
+					failures.append(code)
+
+		threads = [threading.Thread(target=run_queries) for _ in range(8)]
+		for thread in threads:
+			thread.start()
+		for thread in threads:
+			thread.join()
+		self.assertEqual(failures, [])
+
+	def test_cancel_in_flight_request(self):
+		# Runs an endless loop as init code, which keeps the request busy until it runs out of gas.
+		request = (b'{"method":"eth_call","params":[{"gas":"0x3b9aca00","data":"0x5b600056"}],'
+			b'"id":1,"jsonrpc":"2.0"}')
+		pending = CancelableRequest(self.lib, self.client, request)
+		canceller = threading.Timer(0.1, pending.cancel)
+		canceller.start()
+		self.assertIsNone(pending.wait(timeout=30))
+		canceller.join()
+		self.assertTrue(pending.cancelled)
+
+		# The client keeps answering the following requests.
+		request = b'{"method":"eth_blockNumber","params":[],"id":1,"jsonrpc":"2.0"}'
+		response = CancelableRequest(self.lib, self.client, request).wait(timeout=30)
+		self.assertEqual(json.loads(response.decode())["result"], "0x0")
+
+	def test_abort_completed_request(self):
+		# Aborting a request that already completed does nothing.
+		request = b'{"method":"web3_clientVersion","params":[],"id":1,"jsonrpc":"2.0"}'
+		response = CancelableRequest(self.lib, self.client, request).wait(timeout=30)
+		self.assertIn("Parity", json.loads(response.decode())["result"])
+
+
+class MultipleInstancesTest(unittest.TestCase):
+	def test_two_instances(self):
+		lib = load_library()
+		base_paths = [tempfile.mkdtemp(), tempfile.mkdtemp()]
+		clients = [start_client(lib, path, port) for path, port in zip(base_paths, [30400, 30401])]
+		try:
+			self.assertNotEqual(clients[0].value, clients[1].value)
+			request = b'{"method":"eth_blockNumber","params":[],"id":1,"jsonrpc":"2.0"}'
+			for client in clients:
+				code, response = rpc_sync(lib, client, request)
+				self.assertEqual(code, PARITY_OK)
+				self.assertEqual(json.loads(response.decode())["result"], "0x0")
+		finally:
+			for client in clients:
+				lib.parity_destroy(client)
+			for path in base_paths:
+				shutil.rmtree(path, ignore_errors=True)
+
+
+if __name__ == "__main__":
+	unittest.main()
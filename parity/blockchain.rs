@@ -37,6 +37,7 @@ use informant::{Informant, FullNodeInformantData, MillisecondDuration};
 use params::{SpecType, Pruning, Switch, tracing_switch_to_bool, fatdb_switch_to_bool};
 use helpers::{to_client_config, execute_upgrades};
 use dir::Directories;
+use run::RunCmd;
 use user_defaults::UserDefaults;
 use ethcore_private_tx;
 use db;
@@ -709,6 +710,33 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	Ok(())
 }
 
+/// Reverts the chain of the node that `cmd` runs to the block with the given number, so that the
+/// blocks after it are imported again. The node must not be running.
+pub fn revert_chain(cmd: RunCmd, block_number: u64) -> Result<(), String> {
+	if cmd.light {
+		return Err("Light clients have no chain to revert".into());
+	}
+	if cmd.in_memory {
+		return Err("The in-memory database is gone once the node is shut down".into());
+	}
+
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		cmd.tracing,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.cache_config,
+		false,
+		cmd.max_round_blocks_to_import,
+	)?;
+
+	service.client().revert_to(block_number)
+}
+
 pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
 	let genesis_hash = spec.genesis_header().hash();
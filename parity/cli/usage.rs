@@ -142,7 +142,7 @@ macro_rules! usage {
 		}
 	) => {
 		use toml;
-		use std::{fs, io, process, cmp};
+		use std::{fs, io, process, cmp, fmt};
 		use std::io::{Read, Write};
 		use parity_version::version;
 		use clap::{Arg, App, SubCommand, AppSettings, ArgSettings, Error as ClapError, ErrorKind as ClapErrorKind};
@@ -159,11 +159,16 @@ macro_rules! usage {
 		#[cfg(test)]
 		use regex::Regex;
 
+		/// Error while parsing the arguments.
 		#[derive(Debug)]
 		pub enum ArgsError {
+			/// Invalid command line arguments.
 			Clap(ClapError),
+			/// Invalid contents of the config file.
 			Decode(toml::de::Error),
+			/// The config file at the given path couldn't be read.
 			Config(String, io::Error),
+			/// `min_peers` is greater than `max_peers`.
 			PeerConfiguration,
 		}
 
@@ -189,6 +194,17 @@ macro_rules! usage {
 			}
 		}
 
+		impl fmt::Display for ArgsError {
+			fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				match *self {
+					ArgsError::Clap(ref e) => write!(f, "{}", e),
+					ArgsError::Decode(ref e) => write!(f, "Invalid parameters in config file: {}", e),
+					ArgsError::Config(ref path, ref e) => write!(f, "Error reading config file at {}: {}", path, e),
+					ArgsError::PeerConfiguration => write!(f, "You have supplied `min_peers` > `max_peers`"),
+				}
+			}
+		}
+
 		impl From<ClapError> for ArgsError {
 			fn from(e: ClapError) -> Self {
 				ArgsError::Clap(e)
@@ -202,7 +218,7 @@ macro_rules! usage {
 		}
 
 		/// Parsed command line arguments.
-		#[derive(Debug, PartialEq)]
+		#[derive(Debug, PartialEq, Clone)]
 		pub struct Args {
 			$(
 				pub $subc: bool,
@@ -26,7 +26,7 @@ use ethereum_types::{U256, H256, Address};
 use parity_version::{version_data, version};
 use bytes::Bytes;
 use ansi_term::Colour;
-use sync::{NetworkConfiguration, validate_node_url, self};
+use sync::NetworkConfiguration;
 use ethcore::ethstore::ethkey::{Secret, Public};
 use ethcore::client::{VMType};
 use ethcore::miner::{stratum, MinerOptions};
@@ -38,7 +38,7 @@ use num_cpus;
 use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration};
 use parity_rpc::NetworkSettings;
 use cache::CacheConfig;
-use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization, passwords_from_files};
+use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, validate_reserved_peer, to_addresses, to_address, to_queue_strategy, to_queue_penalization, passwords_from_files};
 use dir::helpers::{replace_home, replace_home_and_local};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
 use ethcore_logger::Config as LogConfig;
@@ -92,10 +92,19 @@ pub struct Execute {
 }
 
 /// Configuration for the Parity client.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Configuration {
 	/// Arguments to be interpreted.
 	pub args: Args,
+	/// Chain specification to use instead of the `--chain` argument, as JSON.
+	pub chain_spec_json: Option<String>,
+	/// Whether to keep the blockchain database in memory rather than on disk, for disposable test
+	/// nodes. Unsuitable for real chains, which don't fit in memory and would be synced again on
+	/// every start.
+	pub in_memory: bool,
+	/// Enode URLs of the reserved peers, used instead of the file of the `--reserved-peers`
+	/// argument.
+	pub reserved_peers: Option<Vec<String>>,
 }
 
 impl Configuration {
@@ -109,6 +118,9 @@ impl Configuration {
 	pub fn parse_cli<S: AsRef<str>>(command: &[S]) -> Result<Self, ArgsError> {
 		let config = Configuration {
 			args: Args::parse(command)?,
+			chain_spec_json: None,
+			in_memory: false,
+			reserved_peers: None,
 		};
 
 		Ok(config)
@@ -391,6 +403,7 @@ impl Configuration {
 				serve_light: !self.args.flag_no_serve_light,
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
+				in_memory: self.in_memory,
 				whisper: whisper_config,
 				no_hardcoded_sync: self.args.flag_no_hardcoded_sync,
 				max_round_blocks_to_import: self.args.arg_max_round_blocks_to_import,
@@ -462,6 +475,10 @@ impl Configuration {
 	}
 
 	fn chain(&self) -> Result<SpecType, String> {
+		if let Some(ref json) = self.chain_spec_json {
+			return Ok(SpecType::Json(json.clone()));
+		}
+
 		let name = if self.args.flag_testnet {
 			"testnet".to_owned()
 		} else {
@@ -671,27 +688,24 @@ impl Configuration {
 	fn init_reserved_nodes(&self) -> Result<Vec<String>, String> {
 		use std::fs::File;
 
-		match self.args.arg_reserved_peers {
-			Some(ref path) => {
+		let lines = match (&self.reserved_peers, &self.args.arg_reserved_peers) {
+			(&Some(ref peers), _) => peers.clone(),
+			(&None, &Some(ref path)) => {
 				let path = replace_home(&self.directories().base, path);
 
 				let mut buffer = String::new();
 				let mut node_file = File::open(&path).map_err(|e| format!("Error opening reserved nodes file: {}", e))?;
 				node_file.read_to_string(&mut buffer).map_err(|_| "Error reading reserved node file")?;
-				let lines = buffer.lines().map(|s| s.trim().to_owned()).filter(|s| !s.is_empty() && !s.starts_with("#")).collect::<Vec<_>>();
-
-				for line in &lines {
-					match validate_node_url(line).map(Into::into) {
-						None => continue,
-						Some(sync::ErrorKind::AddressResolve(_)) => return Err(format!("Failed to resolve hostname of a boot node: {}", line)),
-						Some(_) => return Err(format!("Invalid node address format given for a boot node: {}", line)),
-					}
-				}
-
-				Ok(lines)
+				buffer.lines().map(|s| s.trim().to_owned()).filter(|s| !s.is_empty() && !s.starts_with("#")).collect::<Vec<_>>()
 			},
-			None => Ok(Vec::new())
+			(&None, &None) => return Ok(Vec::new()),
+		};
+
+		for line in &lines {
+			validate_reserved_peer(line)?;
 		}
+
+		Ok(lines)
 	}
 
 	fn net_addresses(&self) -> Result<(SocketAddr, Option<SocketAddr>), String> {
@@ -1196,6 +1210,9 @@ mod tests {
 	fn parse(args: &[&str]) -> Configuration {
 		Configuration {
 			args: Args::parse_without_config(args).unwrap(),
+			chain_spec_json: None,
+			in_memory: false,
+			reserved_peers: None,
 		}
 	}
 
@@ -1442,6 +1459,7 @@ mod tests {
 			light: false,
 			no_hardcoded_sync: false,
 			no_persistent_txqueue: false,
+			in_memory: false,
 			whisper: Default::default(),
 			max_round_blocks_to_import: 12,
 			on_demand_retry_count: None,
@@ -19,7 +19,8 @@
 #[path="rocksdb/mod.rs"]
 mod impls;
 
-pub use self::impls::{open_db, restoration_db_handler, migrate};
+pub use self::impls::{open_db, open_memory_db, restoration_db_handler, memory_restoration_db_handler, migrate,
+	is_locked_error, is_permission_denied_error};
 
 #[cfg(feature = "secretstore")]
 pub use self::impls::open_secretstore_db;
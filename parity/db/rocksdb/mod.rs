@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+extern crate kvdb_memorydb;
 extern crate kvdb_rocksdb;
 extern crate migration_rocksdb;
 
@@ -26,6 +27,7 @@ use ethcore::db::NUM_COLUMNS;
 use ethcore::client::{ClientConfig, DatabaseCompactionProfile};
 use kvdb::KeyValueDB;
 use self::kvdb_rocksdb::{Database, DatabaseConfig};
+use tempdir::TempDir;
 
 use cache::CacheConfig;
 
@@ -85,6 +87,33 @@ pub fn restoration_db_handler(client_path: &Path, client_config: &ClientConfig)
 	})
 }
 
+/// Create a restoration db handler opening in-memory databases, see `open_memory_db`.
+pub fn memory_restoration_db_handler() -> Box<BlockChainDBHandler> {
+	struct MemoryRestorationDBHandler;
+
+	impl BlockChainDBHandler for MemoryRestorationDBHandler {
+		fn open(&self, _db_path: &Path) -> io::Result<Arc<BlockChainDB>> {
+			open_memory_db()
+		}
+	}
+
+	Box::new(MemoryRestorationDBHandler)
+}
+
+/// Returns true if the database couldn't be opened because another process holds its lock.
+pub fn is_locked_error(err: &io::Error) -> bool {
+	// RocksDB reports a failure to acquire the `LOCK` file as a generic IO error, as it does when
+	// the `LOCK` file can't be opened at all.
+	err.to_string().contains("LOCK:") && !is_permission_denied_error(err)
+}
+
+/// Returns true if the database couldn't be opened because of the permissions of its files.
+pub fn is_permission_denied_error(err: &io::Error) -> bool {
+	// RocksDB only passes the message of the OS error along, as on Unix and Windows respectively.
+	let message = err.to_string();
+	err.kind() == io::ErrorKind::PermissionDenied || message.contains("Permission denied") || message.contains("Access is denied")
+}
+
 /// Open a new main DB.
 pub fn open_db(client_path: &str, cache_config: &CacheConfig, compaction: &DatabaseCompactionProfile) -> io::Result<Arc<BlockChainDB>> {
 	let path = Path::new(client_path);
@@ -98,6 +127,45 @@ pub fn open_db(client_path: &str, cache_config: &CacheConfig, compaction: &Datab
 	open_database(client_path, &db_config)
 }
 
+/// Open a new main DB kept in memory, which is lost when dropped. The blooms, which can't be kept
+/// in memory, are stored in temporary directories removed along with the DB.
+pub fn open_memory_db() -> io::Result<Arc<BlockChainDB>> {
+	struct MemoryDB {
+		key_value: Arc<KeyValueDB>,
+		blooms: blooms_db::Database,
+		trace_blooms: blooms_db::Database,
+		_blooms_dir: TempDir,
+		_trace_blooms_dir: TempDir,
+	}
+
+	impl BlockChainDB for MemoryDB {
+		fn key_value(&self) -> &Arc<KeyValueDB> {
+			&self.key_value
+		}
+
+		fn blooms(&self) -> &blooms_db::Database {
+			&self.blooms
+		}
+
+		fn trace_blooms(&self) -> &blooms_db::Database {
+			&self.trace_blooms
+		}
+	}
+
+	let blooms_dir = TempDir::new("parity-blooms")?;
+	let trace_blooms_dir = TempDir::new("parity-trace-blooms")?;
+
+	let db = MemoryDB {
+		key_value: Arc::new(kvdb_memorydb::create(NUM_COLUMNS.expect("main DB uses columns; qed"))),
+		blooms: blooms_db::Database::open(blooms_dir.path())?,
+		trace_blooms: blooms_db::Database::open(trace_blooms_dir.path())?,
+		_blooms_dir: blooms_dir,
+		_trace_blooms_dir: trace_blooms_dir,
+	};
+
+	Ok(Arc::new(db))
+}
+
 pub fn open_database(client_path: &str, config: &DatabaseConfig) -> io::Result<Arc<BlockChainDB>> {
 	let path = Path::new(client_path);
 
@@ -18,6 +18,7 @@ use std::io;
 use std::io::{Write, BufReader, BufRead};
 use std::time::Duration;
 use std::fs::File;
+use std::net::IpAddr;
 use ethereum_types::{U256, clean_0x, Address};
 use journaldb::Algorithm;
 use ethcore::client::{Mode, BlockId, VMType, DatabaseCompactionProfile, ClientConfig, VerifierType};
@@ -178,6 +179,26 @@ pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
 	}
 }
 
+/// Checks the enode URL of a reserved peer.
+pub fn validate_reserved_peer(peer: &str) -> Result<(), String> {
+	match validate_node_url(peer).map(Into::into) {
+		None => Ok(()),
+		Some(sync::ErrorKind::AddressResolve(_)) => Err(format!("Failed to resolve hostname of a reserved peer: {}", peer)),
+		Some(_) => Err(format!("Invalid node address format given for a reserved peer: {}", peer)),
+	}
+}
+
+/// Checks a method to determine the public address, as given with `--nat`.
+pub fn validate_nat(nat: &str) -> Result<(), String> {
+	match nat {
+		"any" | "none" | "upnp" => Ok(()),
+		_ if nat.starts_with("extip:") => nat[6..].parse::<IpAddr>()
+			.map(|_| ())
+			.map_err(|_| format!("Invalid host given with `--nat {}`", nat)),
+		_ => Err(format!("Invalid NAT method: {}. Must be one of: any, none, upnp, extip:<IP>.", nat)),
+	}
+}
+
 #[cfg(test)]
 pub fn default_network_config() -> ::sync::NetworkConfiguration {
 	use sync::{NetworkConfiguration};
@@ -332,7 +353,7 @@ mod tests {
 	use ethcore::client::{Mode, BlockId};
 	use ethcore::miner::PendingSet;
 	use ethkey::Password;
-	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file};
+	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, validate_nat, password_from_file};
 
 	#[test]
 	fn test_to_duration() {
@@ -472,4 +493,16 @@ but the first password is trimmed
 		assert_eq!(to_bootnodes(&Some(one_bootnode.into())), Ok(vec![one_bootnode.into()]));
 		assert_eq!(to_bootnodes(&Some(two_bootnodes.into())), Ok(vec![one_bootnode.into(), one_bootnode.into()]));
 	}
+
+	#[test]
+	fn test_validate_nat() {
+		assert!(validate_nat("any").is_ok());
+		assert!(validate_nat("none").is_ok());
+		assert!(validate_nat("upnp").is_ok());
+		assert!(validate_nat("extip:1.2.3.4").is_ok());
+		assert!(validate_nat("extip:::1").is_ok());
+		assert!(validate_nat("extip:").is_err());
+		assert!(validate_nat("extip:example.com").is_err());
+		assert!(validate_nat("nat-pmp").is_err());
+	}
 }
@@ -70,6 +70,8 @@ extern crate node_filter;
 extern crate keccak_hash as hash;
 extern crate journaldb;
 extern crate registrar;
+extern crate tempdir;
+extern crate vm;
 
 #[macro_use]
 extern crate log as rlog;
@@ -81,9 +83,6 @@ extern crate ethcore_secretstore;
 #[macro_use]
 extern crate pretty_assertions;
 
-#[cfg(test)]
-extern crate tempdir;
-
 mod account;
 mod blockchain;
 mod cache;
@@ -119,8 +118,16 @@ use ethcore_logger::setup_log;
 #[cfg(feature = "memory_profiling")]
 use std::alloc::System;
 
+pub use self::cli::ArgsError;
 pub use self::configuration::Configuration;
-pub use self::run::RunningClient;
+pub use self::helpers::{to_bootnodes, validate_nat, validate_reserved_peer};
+pub use self::rpc_apis::Api as RpcApi;
+pub use self::run::{BlockImport, BlockSummary, CallOutcome, ClientMode, DATABASE_LOCKED_ERROR, INVALID_PRIVATE_KEY_ERROR, KeyFileError, LIGHT_CLIENT_UNSUPPORTED_ERROR, LogListener, NewBlockListener,
+	NodeHealth, PeerDetails, RunningClient, SEAL_ON_DEMAND_UNSUPPORTED_ERROR, SignMessageError, SyncStatus, SyncStatusReader, TransactionStatus, UnlockError};
+pub use ethcore::log_entry::LocalizedLogEntry;
+pub use ethcore::receipt::{LocalizedReceipt, TransactionOutcome};
+pub use parity_rpc::PubSubSession;
+pub use dir::PERMISSION_DENIED_ERROR;
 
 #[cfg(feature = "memory_profiling")]
 #[global_allocator]
@@ -184,7 +191,7 @@ fn execute<Cr, Rr>(command: Execute, on_client_rq: Cr, on_updater_rq: Rr) -> Res
 {
 	// TODO: move this to `main()` and expose in the C API so that users can setup logging the way
 	// 		they want
-	let logger = setup_log(&command.logger).expect("Logger is initialized only once; qed");
+	let logger = setup_log(&command.logger)?;
 
 	#[cfg(feature = "deadlock_detection")]
 	run_deadlock_detection_thread();
@@ -208,6 +215,19 @@ fn execute<Cr, Rr>(command: Execute, on_client_rq: Cr, on_updater_rq: Rr) -> Res
 	}
 }
 
+/// Reverts the chain of the node that `start` would run with `conf` to the block with the given
+/// number, so that the blocks after it are imported again, for example to recover from a bad block
+/// that was accepted.
+///
+/// The node must not be running, and must be a full node with `--pruning archive`, so that the
+/// state of the block is still available.
+pub fn revert_chain(conf: Configuration, block_number: u64) -> Result<(), String> {
+	match conf.into_command()?.cmd {
+		Cmd::Run(run_cmd) => blockchain::revert_chain(run_cmd, block_number),
+		_ => Err("The configuration doesn't run a node".into()),
+	}
+}
+
 /// Starts the parity client.
 ///
 /// `on_client_rq` is the action to perform when the client receives an RPC request to be restarted
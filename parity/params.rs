@@ -48,6 +48,8 @@ pub enum SpecType {
 	Sokol,
 	Dev,
 	Custom(String),
+	/// Chain specification given as JSON rather than as the path to a file.
+	Json(String),
 }
 
 impl Default for SpecType {
@@ -103,6 +105,7 @@ impl fmt::Display for SpecType {
 			SpecType::Sokol => "sokol",
 			SpecType::Dev => "dev",
 			SpecType::Custom(ref custom) => custom,
+			SpecType::Json(_) => "custom",
 		})
 	}
 }
@@ -130,7 +133,8 @@ impl SpecType {
 			SpecType::Custom(ref filename) => {
 				let file = fs::File::open(filename).map_err(|e| format!("Could not load specification file at {}: {}", filename, e))?;
 				Spec::load(params, file)
-			}
+			},
+			SpecType::Json(ref json) => Spec::load(params, json.as_bytes()),
 		}
 	}
 
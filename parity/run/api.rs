@@ -0,0 +1,1197 @@
+// Copyright 2015-2018 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Methods of `RunningClient` to embed the client, with the types they return.
+
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use bytes::Bytes;
+use ethcore::account_provider::SignError;
+use ethcore::client::{Balance, BlockId, Call, CallAnalytics, CallError, TransactionId, ChainInfo, ChainNotify, ChainRoute, ChainRouteType, Client, BlockChainClient, BlockInfo, ImportBlock, ProvingBlockChainClient};
+use ethcore::engines::Engine;
+use ethcore::error::{ImportErrorKind, ErrorKind as EthcoreErrorKind, Error as EthcoreError};
+use ethcore::filter::Filter;
+use ethcore::header::Header;
+use ethcore::log_entry::LocalizedLogEntry;
+use ethcore::ethstore::{self, ethkey};
+use ethcore::receipt::LocalizedReceipt;
+use ethcore::miner::{MinerService, PendingOrdering};
+use ethcore::snapshot::{SnapshotService, RestorationStatus};
+use ethcore::snapshot::io::{LooseReader, PackedReader};
+use ethcore::verification::queue::kind::blocks::Unverified;
+use ethereum_types::{Address, H256, H512, U256};
+use sync::{self, LightSyncProvider, ManageNetwork};
+use miner::pool::local_transactions::Status as LocalTransactionStatus;
+use journaldb::Algorithm;
+use light::client::LightChainNotify;
+use parity_rpc::is_major_importing;
+use parity_rpc::v1::Filter as RpcFilter;
+use rlp::{Rlp, RlpStream};
+use transaction::{Action, SignedTransaction, Transaction, UnverifiedTransaction};
+use vm;
+
+use super::{LightClient, RunningClient, RunningClientInner};
+
+// Number of blocks sampled for the gas price statistics, as by the `parity_gasPriceHistogram` RPC.
+// Full client only.
+const GAS_PRICE_SAMPLE_BLOCKS: usize = 100;
+
+/// Prefix of the error message returned by `RunningClient::import_private_key` when the bytes
+/// aren't a valid secp256k1 private key.
+pub const INVALID_PRIVATE_KEY_ERROR: &str = "Invalid private key";
+
+/// Error returned by the `RunningClient` methods that only full clients support.
+pub const LIGHT_CLIENT_UNSUPPORTED_ERROR: &str = "Not supported by light clients";
+
+/// Error returned by `RunningClient::force_seal_block` when the engine doesn't seal blocks on
+/// demand.
+pub const SEAL_ON_DEMAND_UNSUPPORTED_ERROR: &str = "The engine of the chain doesn't seal blocks on demand";
+
+// Gas of the transactions made by `RunningClient::call` and `RunningClient::estimate_gas`, which
+// is what the `eth_call` and `eth_estimateGas` RPCs give when no gas is set.
+const CALL_GAS: u64 = 500_000_000;
+
+/// Snapshot of the synchronization progress of a running client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncStatus {
+	/// Number of the best block imported locally.
+	pub current_block: u64,
+	/// Number of the highest block seen on the network, or `current_block` if none was seen.
+	pub highest_block: u64,
+	/// Whether the client is performing a major sync.
+	pub is_syncing: bool,
+}
+
+impl SyncStatus {
+	fn light(client: &LightClient, sync: &sync::LightSync) -> Self {
+		let current_block = client.chain_info().best_block_number;
+		SyncStatus {
+			current_block,
+			highest_block: sync.highest_block().unwrap_or(current_block),
+			is_syncing: sync.is_major_importing(),
+		}
+	}
+
+	fn full(client: &Client, sync: &sync::SyncProvider) -> Self {
+		let current_block = client.chain_info().best_block_number;
+		let status = sync.status();
+		SyncStatus {
+			current_block,
+			highest_block: status.highest_block_number.unwrap_or(current_block),
+			is_syncing: is_major_importing(Some(status.state), client.queue_info()),
+		}
+	}
+}
+
+/// Reads the synchronization status of a client without keeping it alive.
+///
+/// Returned by `RunningClient::sync_status_reader`.
+#[derive(Clone)]
+pub struct SyncStatusReader {
+	inner: SyncStatusReaderInner,
+}
+
+#[derive(Clone)]
+enum SyncStatusReaderInner {
+	Light {
+		client: Weak<LightClient>,
+		sync: Weak<sync::LightSync>,
+	},
+	Full {
+		client: Weak<Client>,
+		sync: Weak<sync::SyncProvider>,
+	},
+}
+
+impl SyncStatusReader {
+	/// Returns the current synchronization status of the client, or `None` if it was shut down.
+	pub fn sync_status(&self) -> Option<SyncStatus> {
+		match self.inner {
+			SyncStatusReaderInner::Light { ref client, ref sync } => {
+				Some(SyncStatus::light(&*client.upgrade()?, &*sync.upgrade()?))
+			},
+			SyncStatusReaderInner::Full { ref client, ref sync } => {
+				Some(SyncStatus::full(&*client.upgrade()?, &*sync.upgrade()?))
+			},
+		}
+	}
+}
+
+/// Health of a running client, as assessed by the `parity_nodeStatus` RPC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeHealth {
+	/// Whether the client is performing a major sync or restoring a snapshot.
+	pub is_syncing: bool,
+	/// Number of connected peers.
+	pub num_peers: usize,
+	/// Whether the number of peers is ignored when assessing the health, as on development chains.
+	pub peers_check_disabled: bool,
+}
+
+impl NodeHealth {
+	/// Returns true if the `parity_nodeStatus` RPC would report the node as healthy.
+	pub fn is_healthy(&self) -> bool {
+		!self.is_syncing && (self.peers_check_disabled || self.num_peers > 0)
+	}
+}
+
+/// Peer connected to a running client, as listed by the `parity_netPeers` RPC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerDetails {
+	/// Enode URL of the peer, if its node id is known.
+	pub enode: Option<String>,
+	/// Client version advertised by the peer.
+	pub client_version: String,
+	/// Version of the protocol the client syncs with, `eth` for full clients and `pip` for light
+	/// clients, and hash of the best block of the peer, if the peer speaks that protocol.
+	pub protocol: Option<(u32, H256)>,
+}
+
+/// Summary of a block of the canonical chain, as returned by `RunningClient::block_summary`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSummary {
+	/// Hash of the block.
+	pub hash: H256,
+	/// Hash of the parent of the block.
+	pub parent_hash: H256,
+	/// Timestamp of the block, in seconds since the Unix epoch.
+	pub timestamp: u64,
+	/// Gas used by the transactions of the block.
+	pub gas_used: U256,
+	/// Gas limit of the block.
+	pub gas_limit: U256,
+	/// Number of transactions in the block.
+	pub transaction_count: usize,
+	/// Hashes of the transactions of the block, in order, if they were requested.
+	pub transaction_hashes: Option<Vec<H256>>,
+}
+
+/// State of a transaction, as returned by `RunningClient::transaction_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+	/// The transaction is neither in the chain nor in the transaction queue.
+	Unknown,
+	/// The transaction is in the transaction queue, ready to be included in the next block.
+	Pending,
+	/// The transaction is in the transaction queue, but can't be included in a block yet, for
+	/// example because a transaction with a lower nonce is missing.
+	Queued,
+	/// The transaction is part of the canonical chain.
+	Mined,
+	/// The transaction was sent through the client but left the transaction queue without being
+	/// mined, for example because it was replaced or the queue was full.
+	Dropped,
+}
+
+/// Kind of client, which tells the state it keeps, as returned by `RunningClient::mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientMode {
+	/// Full client keeping the state of every block, with `--pruning archive`.
+	Archive,
+	/// Full client only keeping the state of the recent blocks, as set with `--pruning-history`.
+	Full,
+	/// Light client, which keeps no state and fetches it from the network.
+	Light,
+}
+
+/// Outcome of `RunningClient::import_block`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockImport {
+	/// The block was added to the import queue, or already was in it.
+	Queued,
+	/// The block is already part of the chain.
+	AlreadyInChain,
+	/// The block is invalid, for the given reason.
+	Rejected(String),
+}
+
+/// Outcome of `RunningClient::call`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+	/// The call succeeded and returned the given output.
+	Success(Bytes),
+	/// The call reverted. The output usually holds the ABI-encoded revert reason.
+	Reverted(Bytes),
+	/// The call failed or couldn't be executed, for the given reason.
+	Failed(String),
+}
+
+/// Error of `RunningClient::unlock_account`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnlockError {
+	/// The password doesn't match the account.
+	InvalidPassword,
+	/// The client doesn't manage the account.
+	UnknownAccount,
+	/// The account couldn't be unlocked for the given reason, for example because its key file
+	/// couldn't be read.
+	Failed(String),
+}
+
+/// Error of `RunningClient::export_account` and `RunningClient::import_key_file`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyFileError {
+	/// The password doesn't match the account.
+	InvalidPassword,
+	/// The client doesn't manage the account.
+	UnknownAccount,
+	/// The key file isn't a JSON key file of the key store.
+	InvalidKeyFile(String),
+	/// The key store couldn't be read or written for the given reason.
+	Failed(String),
+}
+
+/// Error of `RunningClient::sign_message`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignMessageError {
+	/// The account is locked.
+	Locked,
+	/// The client doesn't manage the account.
+	UnknownAccount,
+	/// The message couldn't be signed for the given reason.
+	Failed(String),
+}
+
+/// Listener registered with `RunningClient::add_new_block_listener`.
+///
+/// The listener is unregistered when this handle is dropped.
+pub struct NewBlockListener {
+	_notifier: Arc<Any + Send + Sync>,
+}
+
+// Forwards the blocks enacted on the canonical chain of a full client to a closure.
+struct FullNewBlockNotifier<F> {
+	client: Weak<Client>,
+	on_block: F,
+}
+
+impl<F: Fn(u64, H256) + Send + Sync> ChainNotify for FullNewBlockNotifier<F> {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Bytes>, _duration: Duration) {
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+
+		for hash in route.enacted() {
+			if let Some(header) = client.block_header(BlockId::Hash(*hash)) {
+				(self.on_block)(header.number(), *hash);
+			}
+		}
+	}
+}
+
+// Forwards the headers imported by a light client to a closure.
+struct LightNewBlockNotifier<F> {
+	client: Weak<LightClient>,
+	on_block: F,
+}
+
+impl<F: Fn(u64, H256) + Send + Sync> LightChainNotify for LightNewBlockNotifier<F> {
+	fn new_headers(&self, good: &[H256]) {
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+
+		for hash in good {
+			if let Some(header) = client.block_header(BlockId::Hash(*hash)) {
+				(self.on_block)(header.number(), *hash);
+			}
+		}
+	}
+}
+
+/// Listener registered with `RunningClient::add_log_listener`.
+///
+/// The listener is unregistered when this handle is dropped.
+pub struct LogListener {
+	_notifier: Arc<Any + Send + Sync>,
+}
+
+// Forwards the logs matching a filter of the blocks enacted on or retracted from the canonical
+// chain of a full client to a closure, in the order of the route, as `eth_subscribe` does.
+struct FullLogNotifier<F> {
+	client: Weak<Client>,
+	filter: Filter,
+	on_log: F,
+}
+
+impl<F: Fn(&LocalizedLogEntry, bool) + Send + Sync> ChainNotify for FullLogNotifier<F> {
+	fn new_blocks(&self, _imported: Vec<H256>, _invalid: Vec<H256>, route: ChainRoute, _sealed: Vec<H256>, _proposed: Vec<Bytes>, _duration: Duration) {
+		let client = match self.client.upgrade() {
+			Some(client) => client,
+			None => return,
+		};
+
+		for &(hash, ref route_type) in route.route() {
+			let mut filter = self.filter.clone();
+			filter.from_block = BlockId::Hash(hash);
+			filter.to_block = BlockId::Hash(hash);
+
+			let removed = match *route_type {
+				ChainRouteType::Enacted => false,
+				ChainRouteType::Retracted => true,
+			};
+			for log in client.logs(filter).unwrap_or_default() {
+				(self.on_log)(&log, removed);
+			}
+		}
+	}
+}
+
+impl RunningClient {
+	/// Returns the current synchronization status of the client.
+	pub fn sync_status(&self) -> SyncStatus {
+		match self.inner {
+			RunningClientInner::Light { ref client, ref sync, .. } => SyncStatus::light(client, sync),
+			RunningClientInner::Full { ref client, ref sync, .. } => SyncStatus::full(client, &**sync),
+		}
+	}
+
+	/// Returns a reader of the synchronization status that can outlive this handle, for example
+	/// to poll the status from a timer.
+	pub fn sync_status_reader(&self) -> SyncStatusReader {
+		let inner = match self.inner {
+			RunningClientInner::Light { ref client, ref sync, .. } => {
+				SyncStatusReaderInner::Light { client: Arc::downgrade(client), sync: Arc::downgrade(sync) }
+			},
+			RunningClientInner::Full { ref client, ref sync, .. } => {
+				SyncStatusReaderInner::Full { client: Arc::downgrade(client), sync: Arc::downgrade(sync) }
+			},
+		};
+		SyncStatusReader { inner }
+	}
+
+	/// Returns the number and hash of the best block of the chain.
+	pub fn best_block(&self) -> (u64, H256) {
+		let chain_info = match self.inner {
+			RunningClientInner::Light { ref client, .. } => client.chain_info(),
+			RunningClientInner::Full { ref client, .. } => client.chain_info(),
+		};
+		(chain_info.best_block_number, chain_info.best_block_hash)
+	}
+
+	/// Returns the number of peers the client is connected to.
+	pub fn peer_count(&self) -> usize {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.peer_numbers().connected,
+			RunningClientInner::Full { ref sync, .. } => sync.status().num_peers,
+		}
+	}
+
+	/// Returns the peers the client is connected to.
+	pub fn peers(&self) -> Vec<PeerDetails> {
+		let (peers, is_light) = match self.inner {
+			RunningClientInner::Light { ref sync, .. } => (sync.peers(), true),
+			RunningClientInner::Full { ref sync, .. } => (sync.peers(), false),
+		};
+
+		peers.into_iter().map(|peer| {
+			let protocol = if is_light {
+				peer.pip_info.map(|info| (info.version, info.head))
+			} else {
+				peer.eth_info.map(|info| (info.version, info.head))
+			};
+			PeerDetails {
+				enode: peer.id.map(|id| format!("enode://{}@{}", id, peer.remote_address)),
+				client_version: peer.client_version,
+				protocol,
+			}
+		}).collect()
+	}
+
+	/// Returns the enode URL of the client, or `None` if the network is disabled.
+	pub fn enode(&self) -> Option<String> {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.enode(),
+			RunningClientInner::Full { ref sync, .. } => sync.enode(),
+		}
+	}
+
+	/// Returns the kind of client, from the `--light` and `--pruning` settings it was started with.
+	pub fn mode(&self) -> ClientMode {
+		match self.inner {
+			RunningClientInner::Light { .. } => ClientMode::Light,
+			RunningClientInner::Full { pruning: Algorithm::Archive, .. } => ClientMode::Archive,
+			RunningClientInner::Full { .. } => ClientMode::Full,
+		}
+	}
+
+	/// Returns the chain id used to sign transactions as per EIP-155, or `None` if replay
+	/// protection isn't active at the best block yet.
+	pub fn chain_id(&self) -> Option<u64> {
+		match self.inner {
+			RunningClientInner::Light { ref client, .. } => client.signing_chain_id(),
+			RunningClientInner::Full { ref client, .. } => client.signing_chain_id(),
+		}
+	}
+
+	/// Returns the name of the latest Ethereum hard fork whose EVM rules apply at the best block:
+	/// "frontier", "homestead", "tangerine_whistle", "spurious_dragon", "byzantium" or
+	/// "constantinople".
+	pub fn active_fork(&self) -> &'static str {
+		let (best_block, _) = self.best_block();
+		let schedule = match self.inner {
+			RunningClientInner::Light { ref client, .. } => client.engine().schedule(best_block),
+			RunningClientInner::Full { ref client, .. } => client.engine().schedule(best_block),
+		};
+
+		// Each fork is identified by one of the EIPs it introduced.
+		if schedule.have_bitwise_shifting {
+			"constantinople"
+		} else if schedule.have_return_data {
+			"byzantium"
+		} else if schedule.kill_empty {
+			"spurious_dragon"
+		} else if schedule.sub_gas_cap_divisor.is_some() {
+			"tangerine_whistle"
+		} else if schedule.have_delegate_call {
+			"homestead"
+		} else {
+			"frontier"
+		}
+	}
+
+	/// Returns the id of the network the client connects to.
+	pub fn network_id(&self) -> u64 {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.network_id(),
+			RunningClientInner::Full { ref sync, .. } => sync.status().network_id,
+		}
+	}
+
+	/// Stops the network service, disconnecting all peers. The client and its database are kept
+	/// alive, and the network can be restarted with `start_network`.
+	pub fn stop_network(&self) {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.stop_network(),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.stop_network(),
+		}
+	}
+
+	/// Starts the network service again after `stop_network`.
+	pub fn start_network(&self) {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.start_network(),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.start_network(),
+		}
+	}
+
+	/// Changes the maximum number of incoming and outgoing peers of the running node.
+	///
+	/// The node keeps dialing peers until `max_out` are connected and accepts up to `max_in`
+	/// incoming connections (or half of `max_out` if that is larger). Non-reserved peers above
+	/// the new limits are disconnected.
+	///
+	/// The network only knows a range of peers, so this sets its minimum to `max_out` and its
+	/// maximum to `max_out + max_in`, replacing both `--min-peers` and `--max-peers`.
+	pub fn set_peer_limits(&self, max_in: u32, max_out: u32) {
+		let max_peers = max_out.saturating_add(max_in);
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.set_peers_range(max_out, max_peers),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.set_peers_range(max_out, max_peers),
+		}
+	}
+
+	/// Returns the TCP port the node accepts peers on and the UDP port of its discovery, which
+	/// differ from the configured ones when those are 0, or `None` if the network isn't running.
+	pub fn p2p_ports(&self) -> Option<(u16, u16)> {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.listen_ports(),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.listen_ports(),
+		}
+	}
+
+	/// Returns the public key of the node identity on the network, or `None` if the network isn't
+	/// running.
+	pub fn node_public_key(&self) -> Option<H512> {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.node_id(),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.node_id(),
+		}
+	}
+
+	/// Adds a reserved peer, which the node stays connected to even above the peer limits, as the
+	/// `parity_addReservedPeer` RPC does.
+	pub fn add_reserved_peer(&self, enode: String) -> Result<(), String> {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.add_reserved_peer(enode),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.add_reserved_peer(enode),
+		}
+	}
+
+	/// Removes a reserved peer, as the `parity_removeReservedPeer` RPC does.
+	pub fn remove_reserved_peer(&self, enode: String) -> Result<(), String> {
+		match self.inner {
+			RunningClientInner::Light { ref sync, .. } => sync.remove_reserved_peer(enode),
+			RunningClientInner::Full { ref manage_network, .. } => manage_network.remove_reserved_peer(enode),
+		}
+	}
+
+	/// Returns the addresses of the accounts managed by the client.
+	pub fn accounts(&self) -> Result<Vec<Address>, String> {
+		let account_provider = match self.inner {
+			RunningClientInner::Light { ref account_provider, .. } => account_provider,
+			RunningClientInner::Full { ref account_provider, .. } => account_provider,
+		};
+		account_provider.accounts().map_err(|e| format!("Unable to read the accounts: {}", e))
+	}
+
+	/// Unlocks an account for signing during `duration`, or until the client stops if `duration`
+	/// is zero, as the `personal_unlockAccount` RPC does.
+	pub fn unlock_account(&self, address: Address, password: String, duration: Duration) -> Result<(), UnlockError> {
+		let account_provider = match self.inner {
+			RunningClientInner::Light { ref account_provider, .. } => account_provider,
+			RunningClientInner::Full { ref account_provider, .. } => account_provider,
+		};
+
+		let result = if duration == Duration::from_secs(0) {
+			account_provider.unlock_account_permanently(address, password.into())
+		} else {
+			account_provider.unlock_account_timed(address, password.into(), duration)
+		};
+
+		result.map_err(|err| match err {
+			ethstore::Error::InvalidPassword => UnlockError::InvalidPassword,
+			ethstore::Error::InvalidAccount => UnlockError::UnknownAccount,
+			err => UnlockError::Failed(format!("Unable to unlock the account: {}", err)),
+		})
+	}
+
+	/// Imports a raw private key in the key store, encrypted with `password`, and returns the
+	/// address of the account, as the `parity_newAccountFromSecret` RPC does.
+	pub fn import_private_key(&self, secret: &[u8], password: String) -> Result<Address, String> {
+		let account_provider = match self.inner {
+			RunningClientInner::Light { ref account_provider, .. } => account_provider,
+			RunningClientInner::Full { ref account_provider, .. } => account_provider,
+		};
+
+		let secret = ethkey::Secret::from_unsafe_slice(secret)
+			.map_err(|e| format!("{}: {}", INVALID_PRIVATE_KEY_ERROR, e))?;
+		account_provider.insert_account(secret, &password.into())
+			.map_err(|e| format!("Unable to import the private key: {}", e))
+	}
+
+	/// Returns the JSON key file of an account, in the key store format also used by the other
+	/// clients, as the `parity_exportAccount` RPC does. `password` must be the one of the account.
+	pub fn export_account(&self, address: Address, password: String) -> Result<String, KeyFileError> {
+		let account_provider = match self.inner {
+			RunningClientInner::Light { ref account_provider, .. } => account_provider,
+			RunningClientInner::Full { ref account_provider, .. } => account_provider,
+		};
+
+		let key_file = account_provider.export_account(&address, password.into()).map_err(|err| match err {
+			ethstore::Error::InvalidPassword => KeyFileError::InvalidPassword,
+			ethstore::Error::InvalidAccount => KeyFileError::UnknownAccount,
+			err => KeyFileError::Failed(format!("Unable to export the account: {}", err)),
+		})?;
+		::serde_json::to_string(&key_file)
+			.map_err(|e| KeyFileError::Failed(format!("Unable to serialize the key file: {}", e)))
+	}
+
+	/// Imports a JSON key file in the key store, as the `parity_newAccountFromWallet` RPC does, and
+	/// returns the address of the account. `password` must be the one the key file is encrypted
+	/// with, which the imported account keeps.
+	pub fn import_key_file(&self, json: &[u8], password: String) -> Result<Address, KeyFileError> {
+		let account_provider = match self.inner {
+			RunningClientInner::Light { ref account_provider, .. } => account_provider,
+			RunningClientInner::Full { ref account_provider, .. } => account_provider,
+		};
+
+		account_provider.import_wallet(json, &password.into(), true).map_err(|err| match err {
+			ethstore::Error::InvalidPassword => KeyFileError::InvalidPassword,
+			ethstore::Error::InvalidKeyFile(err) => KeyFileError::InvalidKeyFile(format!("Invalid key file: {}", err)),
+			err => KeyFileError::Failed(format!("Unable to import the key file: {}", err)),
+		})
+	}
+
+	/// Signs a message with an unlocked account, as the `eth_sign` RPC does: the signed hash is
+	/// the Keccak-256 of the message prefixed with "\x19Ethereum Signed Message:\n" and its
+	/// length. Returns the signature as `r`, `s` and `v`, with `v` being 27 or 28.
+	pub fn sign_message(&self, address: Address, message: &[u8]) -> Result<[u8; 65], SignMessageError> {
+		let account_provider = match self.inner {
+			RunningClientInner::Light { ref account_provider, .. } => account_provider,
+			RunningClientInner::Full { ref account_provider, .. } => account_provider,
+		};
+
+		let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+		prefixed.extend_from_slice(message);
+		match account_provider.sign(address, None, ::hash::keccak(prefixed)) {
+			Ok(signature) => Ok(signature.into_electrum()),
+			Err(SignError::NotUnlocked) => Err(SignMessageError::Locked),
+			Err(SignError::NotFound) | Err(SignError::SStore(ethstore::Error::InvalidAccount)) => Err(SignMessageError::UnknownAccount),
+			Err(err) => Err(SignMessageError::Failed(format!("Unable to sign the message: {}", err))),
+		}
+	}
+
+	/// Returns the size of the files of the client database, in bytes. In-memory clients have no
+	/// database files, so their size is 0.
+	pub fn database_size(&self) -> Result<u64, String> {
+		let db_path = match self.inner {
+			RunningClientInner::Light { ref db_path, .. } => db_path,
+			RunningClientInner::Full { ref db_path, .. } => db_path,
+		};
+		let db_path = match *db_path {
+			Some(ref db_path) => db_path,
+			None => return Ok(0),
+		};
+		dir_size(db_path).map_err(|e| format!("Unable to read the size of the database at {}: {}", db_path.display(), e))
+	}
+
+	/// Writes the buffered changes of the client database to disk, for example before backing up
+	/// the data directory.
+	pub fn flush_database(&self) -> Result<(), String> {
+		let result = match self.inner {
+			RunningClientInner::Light { ref client, .. } => client.flush_database(),
+			RunningClientInner::Full { ref client, .. } => client.flush_database(),
+		};
+		result.map_err(|e| format!("Unable to flush the database: {}", e))
+	}
+
+	/// Takes a snapshot of the best block, replacing the local snapshot, and returns the directory
+	/// holding it. Blocks until the snapshot has been written.
+	pub fn take_snapshot(&self) -> Result<PathBuf, String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, ref snapshot, .. } => {
+				let block_number = client.chain_info().best_block_number;
+				snapshot.take_snapshot(&*client, block_number)
+					.map_err(|e| format!("Unable to take a snapshot at #{}: {}", block_number, e))?;
+
+				// The service gives up without error if another snapshot is in progress, or if the
+				// state got pruned in the meantime.
+				match snapshot.manifest() {
+					Some(ref manifest) if manifest.block_number == block_number => Ok(snapshot.snapshot_dir()),
+					_ => Err(format!("Unable to take a snapshot at #{}: another snapshot is in progress or the state was pruned", block_number)),
+				}
+			},
+		}
+	}
+
+	/// Restores the snapshot at `path`, either a directory like the one returned by
+	/// `take_snapshot` or a file written by `parity snapshot`. Blocks until the restoration
+	/// completes.
+	pub fn restore_snapshot(&self, path: &Path) -> Result<(), String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref snapshot, .. } => {
+				if path.is_dir() {
+					let reader = LooseReader::new(path.to_owned())
+						.map_err(|e| format!("Couldn't open snapshot directory: {}", e))?;
+					// Recovering the chunks of the local snapshot would overwrite them while they're read.
+					let recover = path != snapshot.snapshot_dir();
+					::snapshot::restore_using(snapshot.clone(), &reader, recover)
+				} else {
+					let reader = PackedReader::new(path)
+						.map_err(|e| format!("Couldn't open snapshot file: {}", e))?
+						.ok_or_else(|| "Snapshot file has invalid format.".to_owned())?;
+					::snapshot::restore_using(snapshot.clone(), &reader, true)
+				}
+			},
+		}
+	}
+
+	/// Returns the median gas price of the transactions of the recent blocks, as reported by the
+	/// `parity_gasPriceHistogram` RPC.
+	///
+	/// Returns `None` if there are no such transactions, or on light clients if the gas prices
+	/// weren't fetched from the network recently.
+	pub fn gas_price_median(&self) -> Option<U256> {
+		match self.inner {
+			RunningClientInner::Light { ref cache, .. } => {
+				cache.lock().gas_price_corpus().and_then(|corpus| corpus.median().cloned())
+			},
+			RunningClientInner::Full { ref client, .. } => {
+				client.gas_price_corpus(GAS_PRICE_SAMPLE_BLOCKS).median().cloned()
+			},
+		}
+	}
+
+	/// Returns the gas price the `eth_gasPrice` RPC suggests: the median gas price of the recent
+	/// blocks, or on full clients the minimal gas price of the miner if they have no transactions.
+	pub fn suggested_gas_price(&self) -> Option<U256> {
+		match self.inner {
+			RunningClientInner::Light { .. } => self.gas_price_median(),
+			RunningClientInner::Full { ref miner, .. } => {
+				Some(self.gas_price_median().unwrap_or_else(|| miner.sensible_gas_price()))
+			},
+		}
+	}
+
+	/// Returns the given percentile, between 0 and 100, of the gas prices of the transactions of
+	/// the recent blocks. See `gas_price_median`.
+	pub fn gas_price_percentile(&self, percentile: usize) -> Option<U256> {
+		match self.inner {
+			RunningClientInner::Light { ref cache, .. } => {
+				cache.lock().gas_price_corpus().and_then(|corpus| corpus.percentile(percentile).cloned())
+			},
+			RunningClientInner::Full { ref client, .. } => {
+				client.gas_price_corpus(GAS_PRICE_SAMPLE_BLOCKS).percentile(percentile).cloned()
+			},
+		}
+	}
+
+	/// Returns the balance of `address` in the state of the best block, or `None` if that state
+	/// was pruned.
+	pub fn balance(&self, address: &Address) -> Result<Option<U256>, String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => Ok(client.balance(address, BlockId::Latest.into())),
+		}
+	}
+
+	/// Executes a read-only call of the contract at `to` against the state of the best block, as
+	/// the `eth_call` RPC does, from the zero address and without gas price.
+	pub fn call(&self, to: Address, data: Bytes) -> CallOutcome {
+		let client = match self.inner {
+			RunningClientInner::Light { .. } => return CallOutcome::Failed(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => client,
+		};
+
+		let state_pruned = || CallOutcome::Failed("State of the best block is pruned".into());
+		let mut state = match client.state_at(BlockId::Latest) {
+			Some(state) => state,
+			None => return state_pruned(),
+		};
+		let header = match client.block_header(BlockId::Latest).map(|header| header.decode()) {
+			Some(Ok(header)) => header,
+			Some(Err(e)) => return CallOutcome::Failed(format!("Invalid header of the best block: {}", e)),
+			None => return state_pruned(),
+		};
+
+		// Same transaction as the one `eth_call` makes when only `to` and `data` are given.
+		let transaction = Transaction {
+			nonce: 0.into(),
+			action: Action::Call(to),
+			gas: CALL_GAS.into(),
+			gas_price: 0.into(),
+			value: 0.into(),
+			data,
+		}.fake_sign(Address::default());
+
+		match client.call(&transaction, Default::default(), &mut state, &header) {
+			Ok(executed) => match executed.exception {
+				None => CallOutcome::Success(executed.output),
+				Some(vm::Error::Reverted) => CallOutcome::Reverted(executed.output),
+				Some(e) => CallOutcome::Failed(format!("Call failed: {}", e)),
+			},
+			Err(e) => CallOutcome::Failed(format!("Call failed: {}", e)),
+		}
+	}
+
+	/// Estimates the gas a transaction from `from` to `to`, or creating a contract if `to` is
+	/// `None`, would use against the state of the best block, as the `eth_estimateGas` RPC does.
+	pub fn estimate_gas(&self, from: Address, to: Option<Address>, data: Bytes, value: U256) -> Result<U256, String> {
+		let client = match self.inner {
+			RunningClientInner::Light { .. } => return Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => client,
+		};
+
+		let state_pruned = || "State of the best block is pruned".to_owned();
+		let state = client.state_at(BlockId::Latest).ok_or_else(state_pruned)?;
+		let header = match client.block_header(BlockId::Latest).map(|header| header.decode()) {
+			Some(Ok(header)) => header,
+			Some(Err(e)) => return Err(format!("Invalid header of the best block: {}", e)),
+			None => return Err(state_pruned()),
+		};
+
+		// Same transaction as the one `eth_estimateGas` makes when neither the gas, its price nor
+		// the nonce are given. The estimation ignores the nonce and starts from the gas limit of
+		// the block anyway.
+		let transaction = Transaction {
+			nonce: 0.into(),
+			action: to.map_or(Action::Create, Action::Call),
+			gas: CALL_GAS.into(),
+			gas_price: 0.into(),
+			value,
+			data,
+		}.fake_sign(from);
+
+		client.estimate_gas(&transaction, &state, &header)
+			.map_err(|e| format!("Gas estimation failed: {}", e))
+	}
+
+	/// Replays the transaction with the given hash, as the `trace_replayTransaction` RPC does, and
+	/// returns its traces as an RLP list, each trace being encoded as in the trace database.
+	///
+	/// Returns `None` if the transaction is unknown or the state it was executed on was pruned.
+	pub fn replay_transaction_traces(&self, hash: H256) -> Result<Option<Bytes>, String> {
+		let client = match self.inner {
+			RunningClientInner::Light { .. } => return Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => client,
+		};
+
+		let analytics = CallAnalytics { transaction_tracing: true, vm_tracing: false, state_diffing: false };
+		match client.replay(TransactionId::Hash(hash), analytics) {
+			Ok(executed) => Ok(Some(::rlp::encode_list(&executed.trace).to_vec())),
+			Err(CallError::TransactionNotFound) | Err(CallError::StatePruned) => Ok(None),
+			Err(e) => Err(format!("Unable to replay the transaction: {}", e)),
+		}
+	}
+
+	/// Returns the state of the transaction with the given hash, from the chain and the transaction
+	/// queue. Only the transactions sent through the client are known to be dropped.
+	pub fn transaction_status(&self, hash: H256) -> Result<TransactionStatus, String> {
+		let (client, miner) = match self.inner {
+			RunningClientInner::Light { .. } => return Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, ref miner, .. } => (client, miner),
+		};
+
+		// The chain is looked at first, so that a transaction mined in the meantime isn't missed.
+		if client.transaction(TransactionId::Hash(hash)).is_some() {
+			return Ok(TransactionStatus::Mined);
+		}
+		if miner.transaction(&hash).is_some() {
+			return Ok(if miner.pending_transaction_hashes(&**client).contains(&hash) {
+				TransactionStatus::Pending
+			} else {
+				TransactionStatus::Queued
+			});
+		}
+
+		Ok(match miner.local_transactions().get(&hash) {
+			None => TransactionStatus::Unknown,
+			Some(&LocalTransactionStatus::Pending(_)) => TransactionStatus::Queued,
+			Some(&LocalTransactionStatus::Mined(_)) => TransactionStatus::Mined,
+			Some(_) => TransactionStatus::Dropped,
+		})
+	}
+
+	/// Returns the summary of the block with the given number in the canonical chain, with the
+	/// hashes of its transactions if `with_transactions` is true, or `None` if there is no such
+	/// block.
+	pub fn block_summary(&self, number: u64, with_transactions: bool) -> Result<Option<BlockSummary>, String> {
+		let client = match self.inner {
+			RunningClientInner::Light { .. } => return Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => client,
+		};
+
+		Ok(client.block(BlockId::Number(number)).map(|block| BlockSummary {
+			hash: block.hash(),
+			parent_hash: block.parent_hash(),
+			timestamp: block.timestamp(),
+			gas_used: block.gas_used(),
+			gas_limit: block.gas_limit(),
+			transaction_count: block.transactions_count(),
+			transaction_hashes: if with_transactions { Some(block.transaction_hashes()) } else { None },
+		}))
+	}
+
+	/// Returns the receipt of the transaction with the given hash, read from the receipts stored
+	/// with its block, or `None` if the transaction isn't part of the canonical chain.
+	pub fn transaction_receipt(&self, hash: H256) -> Result<Option<LocalizedReceipt>, String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => Ok(client.transaction_receipt(TransactionId::Hash(hash))),
+		}
+	}
+
+	/// Proves the account at `address` and the values at `storage_keys` in its storage against the
+	/// state root of the best block, with the Merkle proofs the light protocol serves.
+	///
+	/// The proofs are returned as the RLP list `[account, account_proof, storage_proofs]`, where
+	/// `account` is the list `[nonce, balance, storage_root, code_hash]`, `account_proof` the
+	/// list of the trie nodes from the root of the state, and `storage_proofs` a list of `[key,
+	/// value, proof]` entries, in the order of `storage_keys`. Missing accounts and values are
+	/// proven absent, with a default account and a zero value.
+	///
+	/// Returns `None` if the state of the best block was pruned.
+	pub fn prove_account_storage(&self, address: Address, storage_keys: &[H256]) -> Result<Option<Bytes>, String> {
+		let client = match self.inner {
+			RunningClientInner::Light { .. } => return Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => client,
+		};
+
+		// All the proofs must be against the same state, even if a new block is imported meanwhile.
+		let block = BlockId::Hash(client.chain_info().best_block_hash);
+		let address_hash = ::hash::keccak(&address);
+		let (account_proof, account) = match client.prove_account(address_hash, block) {
+			Some(proof) => proof,
+			None => return Ok(None),
+		};
+
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&account);
+		stream.append_list::<Vec<u8>, _>(&account_proof);
+		stream.begin_list(storage_keys.len());
+		for key in storage_keys {
+			let (proof, value) = match client.prove_storage(address_hash, ::hash::keccak(key), block) {
+				Some(proof) => proof,
+				None => return Ok(None),
+			};
+			stream.begin_list(3);
+			stream.append(key);
+			stream.append(&value);
+			stream.append_list::<Vec<u8>, _>(&proof);
+		}
+		Ok(Some(stream.out()))
+	}
+
+	/// Returns the value at `key` in the storage of `address` in the state of the best block, or
+	/// `None` if that state was pruned.
+	pub fn storage_at(&self, address: &Address, key: &H256) -> Result<Option<H256>, String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => Ok(client.storage_at(address, key, BlockId::Latest.into())),
+		}
+	}
+
+	/// Calls `on_block` with the number and hash of each new block of the chain, from the thread
+	/// that imported it.
+	///
+	/// The listener stays registered for as long as the returned handle is alive.
+	pub fn add_new_block_listener<F>(&self, on_block: F) -> NewBlockListener
+		where F: Fn(u64, H256) + Send + Sync + 'static
+	{
+		match self.inner {
+			RunningClientInner::Light { ref client, .. } => {
+				let notifier = Arc::new(LightNewBlockNotifier { client: Arc::downgrade(client), on_block });
+				client.add_listener(Arc::downgrade(&(notifier.clone() as Arc<LightChainNotify>)));
+				NewBlockListener { _notifier: notifier }
+			},
+			RunningClientInner::Full { ref client, .. } => {
+				let notifier = Arc::new(FullNewBlockNotifier { client: Arc::downgrade(client), on_block });
+				client.add_notify(notifier.clone());
+				NewBlockListener { _notifier: notifier }
+			},
+		}
+	}
+
+	/// Registers a closure called with the logs matching a filter, given in the JSON format of the
+	/// `eth_subscribe("logs")` RPC, as blocks are added to or removed from the canonical chain.
+	/// The closure also gets whether the log was removed by a reorganization of the chain.
+	///
+	/// The block range of the filter is ignored, and its limit applies to each block.
+	pub fn add_log_listener<F>(&self, filter_json: &str, on_log: F) -> Result<LogListener, String>
+		where F: Fn(&LocalizedLogEntry, bool) + Send + Sync + 'static
+	{
+		let client = match self.inner {
+			RunningClientInner::Light { .. } => return Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, .. } => client,
+		};
+
+		let filter = ::serde_json::from_str::<RpcFilter>(filter_json)
+			.map_err(|e| format!("Invalid filter: {}", e))?
+			.try_into()
+			.map_err(|e| format!("Invalid filter: {}", e.message))?;
+
+		let notifier = Arc::new(FullLogNotifier { client: Arc::downgrade(client), filter, on_log });
+		client.add_notify(notifier.clone());
+		Ok(LogListener { _notifier: notifier })
+	}
+
+	/// Returns the address the HTTP JSON-RPC server is bound to, or `None` if it is disabled.
+	pub fn jsonrpc_http_address(&self) -> Option<SocketAddr> {
+		match self.inner {
+			RunningClientInner::Light { http_address, .. } => http_address,
+			RunningClientInner::Full { http_address, .. } => http_address,
+		}
+	}
+
+	/// Imports a raw signed transaction into the transaction queue, performing the same checks as
+	/// the `eth_sendRawTransaction` RPC.
+	///
+	/// Returns the hash of the transaction, or the reason why it was rejected.
+	pub fn send_raw_transaction(&self, raw: &[u8]) -> Result<H256, String> {
+		let transaction: UnverifiedTransaction = Rlp::new(raw).as_val()
+			.map_err(|e| format!("Invalid RLP: {}", e))?;
+
+		match self.inner {
+			RunningClientInner::Light { ref client, ref transaction_queue, .. } => {
+				let best_header = client.best_block_header().decode()
+					.map_err(|e| format!("Invalid best block header: {}", e))?;
+				client.engine().verify_transaction_basic(&transaction, &best_header)
+					.map_err(|e| e.to_string())?;
+
+				let signed = SignedTransaction::new(transaction).map_err(|e| e.to_string())?;
+				let hash = signed.hash();
+				transaction_queue.write().import(signed.into())
+					.map(|_| hash)
+					.map_err(|e| e.to_string())
+			},
+			RunningClientInner::Full { ref client, ref miner, .. } => {
+				let signed = SignedTransaction::new(transaction).map_err(|e| e.to_string())?;
+				let hash = signed.hash();
+				miner.import_claimed_local_transaction(&**client, signed.into(), false)
+					.map(|_| hash)
+					.map_err(|e| e.to_string())
+			},
+		}
+	}
+
+	/// Returns the number of transactions of the queue that are ready to be included in the next
+	/// block, and the number of the other ones, which wait for a transaction with a lower nonce.
+	///
+	/// The queue of a light client only holds the transactions submitted through it.
+	pub fn transaction_queue_status(&self) -> (usize, usize) {
+		match self.inner {
+			RunningClientInner::Light { ref client, ref transaction_queue, .. } => {
+				let chain_info = client.chain_info();
+				let queue = transaction_queue.read();
+				let pending = queue.ready_transactions(chain_info.best_block_number, chain_info.best_block_timestamp).len();
+				let queued = queue.future_transactions(chain_info.best_block_number, chain_info.best_block_timestamp).len();
+				(pending, queued)
+			},
+			RunningClientInner::Full { ref client, ref miner, .. } => {
+				let total = miner.queue_status().status.transaction_count;
+				let pending = miner.ready_transactions(&**client, usize::max_value(), PendingOrdering::Priority).len();
+				(pending, total.saturating_sub(pending))
+			},
+		}
+	}
+
+	/// Submits an RLP-encoded block to the import queue, as if it had been received from the
+	/// network. Light clients only import the header of the block.
+	pub fn import_block(&self, raw: &[u8]) -> BlockImport {
+		let result = match self.inner {
+			RunningClientInner::Light { ref client, .. } => {
+				let header: Header = match Rlp::new(raw).val_at(0) {
+					Ok(header) => header,
+					Err(e) => return BlockImport::Rejected(format!("Invalid block RLP: {}", e)),
+				};
+				client.import_header(header)
+			},
+			RunningClientInner::Full { ref client, .. } => {
+				let block = match Unverified::from_rlp(raw.to_vec()) {
+					Ok(block) => block,
+					Err(e) => return BlockImport::Rejected(format!("Invalid block RLP: {}", e)),
+				};
+				client.import_block(block)
+			},
+		};
+
+		match result {
+			Ok(_) => BlockImport::Queued,
+			Err(EthcoreError(EthcoreErrorKind::Import(ImportErrorKind::AlreadyQueued), _)) => BlockImport::Queued,
+			Err(EthcoreError(EthcoreErrorKind::Import(ImportErrorKind::AlreadyInChain), _)) => BlockImport::AlreadyInChain,
+			Err(e) => BlockImport::Rejected(e.to_string()),
+		}
+	}
+
+	/// Sets the author of the blocks sealed by the client. Light clients don't seal blocks.
+	pub fn set_author(&self, author: Address) -> Result<(), String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref miner, .. } => {
+				miner.set_author(author, None).map_err(|e| e.to_string())
+			},
+		}
+	}
+
+	/// Sets the minimal gas price of the transactions accepted by the queue, replacing the
+	/// automatic calibration if any. Light clients have no such queue.
+	pub fn set_min_gas_price(&self, gas_price: U256) -> Result<(), String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, ref miner, .. } => {
+				miner.set_minimal_gas_price(gas_price, *client.best_block_header().gas_limit());
+				Ok(())
+			},
+		}
+	}
+
+	/// Seals a new block right away, even if there are no pending transactions, and returns its
+	/// number. Only works on chains whose engine seals internally, such as development chains.
+	pub fn force_seal_block(&self) -> Result<u64, String> {
+		match self.inner {
+			RunningClientInner::Light { .. } => Err(LIGHT_CLIENT_UNSUPPORTED_ERROR.into()),
+			RunningClientInner::Full { ref client, ref miner, .. } => {
+				if client.engine().seals_internally() != Some(true) {
+					return Err(SEAL_ON_DEMAND_UNSUPPORTED_ERROR.into());
+				}
+				if !miner.force_seal_block(&**client) {
+					return Err("Failed to seal a new block".into());
+				}
+				Ok(client.chain_info().best_block_number)
+			},
+		}
+	}
+
+	/// Returns the health of the client.
+	pub fn health(&self) -> NodeHealth {
+		match self.inner {
+			RunningClientInner::Light { ref sync, is_dev_chain, .. } => {
+				NodeHealth {
+					is_syncing: sync.is_major_importing(),
+					num_peers: sync.peer_numbers().connected,
+					peers_check_disabled: is_dev_chain,
+				}
+			},
+			RunningClientInner::Full { ref client, ref sync, ref snapshot, is_dev_chain, .. } => {
+				let status = sync.status();
+				let is_warping = match snapshot.status() {
+					RestorationStatus::Ongoing { .. } => true,
+					_ => false,
+				};
+				NodeHealth {
+					is_syncing: is_warping || is_major_importing(Some(status.state), client.queue_info()),
+					num_peers: status.num_peers,
+					peers_check_disabled: is_dev_chain,
+				}
+			},
+		}
+	}
+
+	/// Registers a closure called once the JSON-RPC servers of the client, HTTP, WebSockets and
+	/// IPC, are bound and accept connections. As the servers are bound while the client starts,
+	/// the closure is called right away, from the calling thread, for a client that is running.
+	pub fn add_rpc_ready_listener<F>(&self, on_ready: F)
+		where F: Fn() + Send + 'static
+	{
+		let mut rpc_ready = self.rpc_ready.lock();
+		if rpc_ready.bound {
+			drop(rpc_ready);
+			on_ready();
+		} else {
+			rpc_ready.listeners.push(Box::new(on_ready));
+		}
+	}
+
+	/// Registers a closure called once with the error message when a full client fails to write to
+	/// its database. The client doesn't recover from that: the closure is called from the thread
+	/// that hit the failure, which then panics, and the shutdown listeners are called right after.
+	pub fn add_database_error_listener<F>(&self, on_database_error: F)
+		where F: Fn(&str) + Send + 'static
+	{
+		self.exit_listeners.database_error.lock().push(Box::new(on_database_error));
+	}
+
+	/// Registers a closure called once when the client begins shutting down, either because
+	/// `shutdown` was called, from the calling thread, or after a database failure, from the thread
+	/// that hit it. The closures are called in the order they were registered.
+	pub fn add_shutdown_listener<F>(&self, on_shutdown: F)
+		where F: Fn() + Send + 'static
+	{
+		self.exit_listeners.shutdown.lock().push(Box::new(on_shutdown));
+	}
+}
+
+// Adds up the sizes of the files in `path` and its subdirectories.
+fn dir_size(path: &Path) -> io::Result<u64> {
+	let mut size = 0;
+	for entry in fs::read_dir(path)? {
+		let entry = entry?;
+		let metadata = entry.metadata()?;
+		size += if metadata.is_dir() { dir_size(&entry.path())? } else { metadata.len() };
+	}
+	Ok(size)
+}
@@ -15,6 +15,10 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::any::Any;
+use std::io;
+use std::mem;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
 use std::thread;
@@ -22,7 +26,7 @@ use std::thread;
 use ansi_term::Colour;
 use bytes::Bytes;
 use ethcore::account_provider::{AccountProvider, AccountProviderSettings};
-use ethcore::client::{BlockId, CallContract, Client, Mode, DatabaseCompactionProfile, VMType, BlockChainClient, BlockInfo};
+use ethcore::client::{BlockId, CallContract, ChainNotify, Client, Mode, DatabaseCompactionProfile, VMType, BlockChainClient, BlockInfo};
 use ethcore::ethstore::ethkey;
 use ethcore::miner::{stratum, Miner, MinerService, MinerOptions};
 use ethcore::snapshot::{self, SnapshotConfiguration};
@@ -31,7 +35,7 @@ use ethcore::verification::queue::VerifierSettings;
 use ethcore_logger::{Config as LogConfig, RotatingLogger};
 use ethcore_service::ClientService;
 use ethereum_types::Address;
-use sync::{self, SyncConfig};
+use sync::{self, SyncConfig, ManageNetwork};
 use miner::work_notify::WorkPoster;
 use futures::IntoFuture;
 use hash_fetch::{self, fetch};
@@ -40,8 +44,9 @@ use journaldb::Algorithm;
 use light::Cache as LightDataCache;
 use miner::external::ExternalMiner;
 use node_filter::NodeFilter;
+use parking_lot::{Mutex, RwLock};
 use parity_runtime::Runtime;
-use parity_rpc::{Origin, Metadata, NetworkSettings, informant, is_major_importing};
+use parity_rpc::{Origin, Metadata, NetworkSettings, PubSubSession, informant, is_major_importing};
 use updater::{UpdatePolicy, Updater};
 use parity_version::version;
 use ethcore_private_tx::{ProviderConfig, EncryptorConfig, SecretStoreEncryptor};
@@ -51,11 +56,12 @@ use params::{
 };
 use helpers::{to_client_config, execute_upgrades, passwords_from_files};
 use upgrade::upgrade_key_location;
-use dir::{Directories, DatabaseDirectories};
+use dir::{Directories, DatabaseDirectories, PERMISSION_DENIED_ERROR};
 use cache::CacheConfig;
 use user_defaults::UserDefaults;
 use ipfs;
-use jsonrpc_core;
+use jsonrpc_core::{self, FutureResult, FutureResponse};
+use jsonrpc_core::middleware::NoopCallFuture;
 use modules;
 use registrar::{RegistrarClient, Asynchronous};
 use rpc;
@@ -65,6 +71,15 @@ use signer;
 use db;
 use ethkey::Password;
 
+mod api;
+
+pub use self::api::{
+	BlockImport, BlockSummary, CallOutcome, ClientMode, INVALID_PRIVATE_KEY_ERROR, KeyFileError,
+	LIGHT_CLIENT_UNSUPPORTED_ERROR, LogListener, NewBlockListener, NodeHealth, PeerDetails,
+	SEAL_ON_DEMAND_UNSUPPORTED_ERROR, SignMessageError, SyncStatus, SyncStatusReader,
+	TransactionStatus, UnlockError
+};
+
 // how often to take periodic snapshots.
 const SNAPSHOT_PERIOD: u64 = 5000;
 
@@ -78,6 +93,10 @@ const GAS_CORPUS_EXPIRATION_MINUTES: u64 = 60 * 6;
 // Pops along with error messages when a password is missing or invalid.
 const VERIFY_PASSWORD_HINT: &str = "Make sure valid password is present in files passed using `--password` or in the configuration file.";
 
+/// Prefix of the error message returned when the database is already in use by another process,
+/// such as another instance of Parity running with the same data directory.
+pub const DATABASE_LOCKED_ERROR: &str = "Database is locked by another process";
+
 // Full client number of DNS threads
 const FETCH_FULL_NUM_DNS_THREADS: usize = 4;
 
@@ -131,6 +150,7 @@ pub struct RunCmd {
 	pub serve_light: bool,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
+	pub in_memory: bool,
 	pub whisper: ::whisper::Config,
 	pub no_hardcoded_sync: bool,
 	pub max_round_blocks_to_import: usize,
@@ -165,8 +185,7 @@ type LightClient = ::light::client::Client<::light_helpers::EpochFetch>;
 // helper for light execution.
 fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<RunningClient, String> {
 	use light::client as light_client;
-	use sync::{LightSyncParams, LightSync, ManageNetwork};
-	use parking_lot::{Mutex, RwLock};
+	use sync::{LightSyncParams, LightSync};
 
 	// load spec
 	let spec = cmd.spec.spec(SpecParams::new(cmd.dirs.cache.as_ref(), OptimizeFor::Memory))?;
@@ -229,9 +248,14 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	};
 
 	// initialize database.
-	let db = db::open_db(&db_dirs.client_path(algorithm).to_str().expect("DB path could not be converted to string."),
-						 &cmd.cache_config,
-						 &cmd.compaction).map_err(|e| format!("Failed to open database {:?}", e))?;
+	let db_path = db_dirs.client_path(algorithm);
+	let db = if cmd.in_memory {
+		db::open_memory_db()
+	} else {
+		db::open_db(&db_path.to_str().expect("DB path could not be converted to string."),
+					&cmd.cache_config,
+					&cmd.compaction)
+	}.map_err(open_db_error)?;
 
 	let service = light_client::Service::start(config, &spec, fetch, db, cache.clone())
 		.map_err(|e| format!("Error starting light client: {}", e))?;
@@ -303,12 +327,12 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 		client: client.clone(),
 		sync: light_sync.clone(),
 		net: light_sync.clone(),
-		secret_store: account_provider,
+		secret_store: account_provider.clone(),
 		logger: logger,
-		settings: Arc::new(cmd.net_settings),
+		settings: Arc::new(cmd.net_settings.clone()),
 		on_demand: on_demand,
 		cache: cache.clone(),
-		transaction_queue: txq,
+		transaction_queue: txq.clone(),
 		ws_address: cmd.ws_conf.address(),
 		fetch: fetch,
 		geth_compatibility: cmd.geth_compatibility,
@@ -329,14 +353,17 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 	let rpc_direct = rpc::setup_apis(rpc_apis::ApiSet::All, &dependencies);
 	let ws_server = rpc::new_ws(cmd.ws_conf, &dependencies)?;
 	let http_server = rpc::new_http("HTTP JSON-RPC", "jsonrpc", cmd.http_conf.clone(), &dependencies)?;
+	let http_address = http_server.as_ref().map(|server| *server.address());
 	let ipc_server = rpc::new_ipc(cmd.ipc_conf, &dependencies)?;
+	let mut rpc_ready = RpcReady::default();
+	rpc_ready.servers_bound();
 
 	// the informant
 	let informant = Arc::new(Informant::new(
 		LightNodeInformantData {
 			client: client.clone(),
 			sync: light_sync.clone(),
-			cache: cache,
+			cache: cache.clone(),
 		},
 		None,
 		Some(rpc_stats),
@@ -350,8 +377,17 @@ fn execute_light_impl(cmd: RunCmd, logger: Arc<RotatingLogger>) -> Result<Runnin
 			rpc: rpc_direct,
 			informant,
 			client,
+			sync: light_sync,
+			transaction_queue: txq,
+			account_provider,
+			cache,
+			db_path: if cmd.in_memory { None } else { Some(db_path) },
+			is_dev_chain: cmd.net_settings.is_dev_chain,
+			http_address,
 			keep_alive: Box::new((runtime, service, ws_server, http_server, ipc_server)),
-		}
+		},
+		exit_listeners: Arc::new(ExitListeners::default()),
+		rpc_ready: Mutex::new(rpc_ready),
 	})
 }
 
@@ -547,9 +583,13 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	// set network path.
 	net_conf.net_config_path = Some(db_dirs.network_path().to_string_lossy().into_owned());
 
-	let restoration_db_handler = db::restoration_db_handler(&client_path, &client_config);
+	let restoration_db_handler = if cmd.in_memory {
+		db::memory_restoration_db_handler()
+	} else {
+		db::restoration_db_handler(&client_path, &client_config)
+	};
 	let client_db = restoration_db_handler.open(&client_path)
-		.map_err(|e| format!("Failed to open database {:?}", e))?;
+		.map_err(open_db_error)?;
 
 	// create client service.
 	let service = ClientService::start(
@@ -732,13 +772,16 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	let ws_server = rpc::new_ws(cmd.ws_conf.clone(), &dependencies)?;
 	let ipc_server = rpc::new_ipc(cmd.ipc_conf, &dependencies)?;
 	let http_server = rpc::new_http("HTTP JSON-RPC", "jsonrpc", cmd.http_conf.clone(), &dependencies)?;
+	let http_address = http_server.as_ref().map(|server| *server.address());
+	let mut rpc_ready = RpcReady::default();
+	rpc_ready.servers_bound();
 
 	// secret store key server
 	let secretstore_deps = secretstore::Dependencies {
 		client: client.clone(),
 		sync: sync_provider.clone(),
-		miner: miner,
-		account_provider: account_provider,
+		miner: miner.clone(),
+		account_provider: account_provider.clone(),
 		accounts_passwords: &passwords,
 	};
 	let secretstore_key_server = secretstore::start(cmd.secretstore_conf.clone(), secretstore_deps)?;
@@ -798,14 +841,30 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 	client.set_exit_handler(on_client_rq);
 	updater.set_exit_handler(on_updater_rq);
 
+	// The client can't go on after a database failure, so it's reported as shutting down.
+	let exit_listeners = Arc::new(ExitListeners::default());
+	let database_error_notifier = Arc::new(DatabaseErrorNotifier { listeners: exit_listeners.clone() });
+	client.add_notify(database_error_notifier.clone());
+
 	Ok(RunningClient {
 		inner: RunningClientInner::Full {
 			rpc: rpc_direct,
 			informant,
 			client,
 			client_service: Arc::new(service),
-			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, secretstore_key_server, ipfs_server, runtime)),
-		}
+			sync: sync_provider,
+			manage_network,
+			miner,
+			snapshot: snapshot_service,
+			account_provider,
+			db_path: if cmd.in_memory { None } else { Some(client_path) },
+			is_dev_chain: cmd.net_settings.is_dev_chain,
+			pruning: algorithm,
+			http_address,
+			keep_alive: Box::new((watcher, updater, ws_server, http_server, ipc_server, secretstore_key_server, ipfs_server, runtime, database_error_notifier)),
+		},
+		exit_listeners,
+		rpc_ready: Mutex::new(rpc_ready),
 	})
 }
 
@@ -815,6 +874,62 @@ fn execute_impl<Cr, Rr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq:
 /// background.
 pub struct RunningClient {
 	inner: RunningClientInner,
+	exit_listeners: Arc<ExitListeners>,
+	rpc_ready: Mutex<RpcReady>,
+}
+
+// Whether the JSON-RPC servers of a client are bound, and the closures registered with
+// `RunningClient::add_rpc_ready_listener` waiting for them until then.
+#[derive(Default)]
+struct RpcReady {
+	bound: bool,
+	listeners: Vec<Box<Fn() + Send>>,
+}
+
+impl RpcReady {
+	// Called once the HTTP, WebSockets and IPC servers are bound, or disabled.
+	fn servers_bound(&mut self) {
+		self.bound = true;
+		for on_ready in self.listeners.drain(..) {
+			on_ready();
+		}
+	}
+}
+
+// Closures registered with `RunningClient::add_database_error_listener` and
+// `RunningClient::add_shutdown_listener` that weren't called yet.
+#[derive(Default)]
+struct ExitListeners {
+	database_error: Mutex<Vec<Box<Fn(&str) + Send>>>,
+	shutdown: Mutex<Vec<Box<Fn() + Send>>>,
+}
+
+impl ExitListeners {
+	fn database_error(&self, error: &str) {
+		let listeners = mem::replace(&mut *self.database_error.lock(), Vec::new());
+		for on_database_error in listeners {
+			on_database_error(error);
+		}
+	}
+
+	fn shutdown(&self) {
+		let listeners = mem::replace(&mut *self.shutdown.lock(), Vec::new());
+		for on_shutdown in listeners {
+			on_shutdown();
+		}
+	}
+}
+
+// Reports the database failures of a full client, after which the client shuts down.
+struct DatabaseErrorNotifier {
+	listeners: Arc<ExitListeners>,
+}
+
+impl ChainNotify for DatabaseErrorNotifier {
+	fn database_error(&self, error: &str) {
+		self.listeners.database_error(error);
+		self.listeners.shutdown();
+	}
 }
 
 enum RunningClientInner {
@@ -822,6 +937,14 @@ enum RunningClientInner {
 		rpc: jsonrpc_core::MetaIoHandler<Metadata, informant::Middleware<rpc_apis::LightClientNotifier>>,
 		informant: Arc<Informant<LightNodeInformantData>>,
 		client: Arc<LightClient>,
+		sync: Arc<sync::LightSync>,
+		transaction_queue: Arc<RwLock<::light::transaction_queue::TransactionQueue>>,
+		account_provider: Arc<AccountProvider>,
+		cache: Arc<Mutex<LightDataCache>>,
+		// `None` for in-memory clients, which have no database files.
+		db_path: Option<PathBuf>,
+		is_dev_chain: bool,
+		http_address: Option<SocketAddr>,
 		keep_alive: Box<Any>,
 	},
 	Full {
@@ -829,6 +952,16 @@ enum RunningClientInner {
 		informant: Arc<Informant<FullNodeInformantData>>,
 		client: Arc<Client>,
 		client_service: Arc<ClientService>,
+		sync: Arc<sync::SyncProvider>,
+		manage_network: Arc<ManageNetwork>,
+		miner: Arc<Miner>,
+		snapshot: Arc<snapshot::Service>,
+		account_provider: Arc<AccountProvider>,
+		// `None` for in-memory clients, which have no database files.
+		db_path: Option<PathBuf>,
+		is_dev_chain: bool,
+		pruning: Algorithm,
+		http_address: Option<SocketAddr>,
 		keep_alive: Box<Any>,
 	},
 }
@@ -852,21 +985,46 @@ impl RunningClient {
 		}
 	}
 
+	/// Performs an asynchronous RPC query.
+	/// The returned future resolves once the result is ready.
+	///
+	/// Subscriptions made through this query deliver their notifications to the given `session`.
+	pub fn rpc_query(&self, request: &str, session: Option<Arc<PubSubSession>>)
+		-> FutureResult<FutureResponse, NoopCallFuture>
+	{
+		let metadata = Metadata {
+			origin: Origin::CApi,
+			session,
+		};
+
+		match self.inner {
+			RunningClientInner::Light { ref rpc, .. } => {
+				rpc.handle_request(request, metadata)
+			},
+			RunningClientInner::Full { ref rpc, .. } => {
+				rpc.handle_request(request, metadata)
+			},
+		}
+	}
+
 	/// Shuts down the client.
 	pub fn shutdown(self) {
+		self.exit_listeners.shutdown();
+
 		match self.inner {
-			RunningClientInner::Light { rpc, informant, client, keep_alive } => {
+			RunningClientInner::Light { rpc, informant, client, sync, keep_alive, .. } => {
 				// Create a weak reference to the client so that we can wait on shutdown
 				// until it is dropped
 				let weak_client = Arc::downgrade(&client);
 				drop(rpc);
+				drop(sync);
 				drop(keep_alive);
 				informant.shutdown();
 				drop(informant);
 				drop(client);
 				wait_for_drop(weak_client);
 			},
-			RunningClientInner::Full { rpc, informant, client, client_service, keep_alive } => {
+			RunningClientInner::Full { rpc, informant, client, client_service, sync, manage_network, miner, snapshot, keep_alive, .. } => {
 				info!("Finishing work, please wait...");
 				// Create a weak reference to the client so that we can wait on shutdown
 				// until it is dropped
@@ -876,6 +1034,10 @@ impl RunningClient {
 				drop(client_service);
 				// drop this stuff as soon as exit detected.
 				drop(rpc);
+				drop(sync);
+				drop(manage_network);
+				drop(miner);
+				drop(snapshot);
 				drop(keep_alive);
 				// to make sure timer does not spawn requests while shutdown is in progress
 				informant.shutdown();
@@ -1000,6 +1162,17 @@ fn insert_dev_account(account_provider: &AccountProvider) {
 	}
 }
 
+// Construct the error `String` returned when the client database can't be opened.
+fn open_db_error(e: io::Error) -> String {
+	if db::is_permission_denied_error(&e) {
+		format!("{}: cannot open database: {}. Does the user running Parity own the data directory?", PERMISSION_DENIED_ERROR, e)
+	} else if db::is_locked_error(&e) {
+		format!("{}: {}. Is another instance of Parity running with the same data directory?", DATABASE_LOCKED_ERROR, e)
+	} else {
+		format!("Failed to open database {:?}", e)
+	}
+}
+
 // Construct an error `String` with an adaptive hint on how to create an account.
 fn build_create_account_hint(spec: &SpecType, keys: &str) -> String {
 	format!("You can create an account via RPC, UI or `parity account new --chain {} --keys-path {}`.", spec, keys)
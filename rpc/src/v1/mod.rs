@@ -45,7 +45,7 @@ pub use self::traits::{Debug, Eth, EthFilter, EthPubSub, EthSigning, Net, Parity
 pub use self::impls::*;
 pub use self::helpers::{NetworkSettings, block_import, dispatch};
 pub use self::metadata::Metadata;
-pub use self::types::Origin;
+pub use self::types::{Filter, Origin};
 pub use self::extractors::{RpcExtractor, WsExtractor, WsStats, WsDispatcher};
 
 /// Signer utilities
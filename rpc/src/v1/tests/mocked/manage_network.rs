@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::ops::Range;
+use ethereum_types::H512;
 use sync::ManageNetwork;
 use self::ethcore_network::{ProtocolId, NetworkContext};
 
@@ -31,5 +32,8 @@ impl ManageNetwork for TestManageNetwork {
 	fn start_network(&self) {}
 	fn stop_network(&self) {}
 	fn num_peers_range(&self) -> Range<u32> { 25 .. 51 }
+	fn set_peers_range(&self, _min_peers: u32, _max_peers: u32) { }
+	fn listen_ports(&self) -> Option<(u16, u16)> { None }
+	fn node_id(&self) -> Option<H512> { None }
 	fn with_proto_context(&self, _: ProtocolId, _: &mut FnMut(&NetworkContext)) { }
 }
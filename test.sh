@@ -68,6 +68,19 @@ cpp_test () {
   esac
 }
 
+interop_test () {
+  case $CARGO_TARGET in
+    (x86_64-unknown-linux-gnu)
+      # The interop tests need the Go and Node.js toolchains, so they're ignored by default
+      echo "________Running the C API interop tests________"
+      time cargo test $OPTIONS --manifest-path parity-clib/Cargo.toml --features napi -- --ignored
+      ;;
+    (*)
+      echo "________Skipping the C API interop tests________"
+      ;;
+  esac
+}
+
 cargo_test () {
   echo "________Running Parity Full Test Suite________"
   git submodule update --init --recursive
@@ -83,6 +96,7 @@ else
 fi
 
 test "${RUN_TESTS}" = "all" && cpp_test
+test "${RUN_TESTS}" = "all" && interop_test
 
 if [ "$CARGO_TARGET" ]
 then
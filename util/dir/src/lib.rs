@@ -24,6 +24,7 @@ extern crate home;
 
 pub mod helpers;
 use std::fs;
+use std::io;
 use std::path::{PathBuf, Path};
 use ethereum_types::{H64, H256};
 use journaldb::Algorithm;
@@ -34,6 +35,10 @@ use platform::*;
 
 pub use home::home_dir;
 
+/// Prefix of the error message returned when a directory can't be created because of the
+/// permissions of its parent.
+pub const PERMISSION_DENIED_ERROR: &str = "Permission denied";
+
 /// Platform-specific chains path for standard client - Windows only
 #[cfg(target_os = "windows")] pub const CHAINS_PATH: &str = "$LOCAL/chains";
 /// Platform-specific chains path for light client - Windows only
@@ -87,15 +92,15 @@ impl Default for Directories {
 impl Directories {
 	/// Create local directories
 	pub fn create_dirs(&self, signer_enabled: bool, secretstore_enabled: bool) -> Result<(), String> {
-		fs::create_dir_all(&self.base).map_err(|e| e.to_string())?;
-		fs::create_dir_all(&self.db).map_err(|e| e.to_string())?;
-		fs::create_dir_all(&self.cache).map_err(|e| e.to_string())?;
-		fs::create_dir_all(&self.keys).map_err(|e| e.to_string())?;
+		create_dir(&self.base)?;
+		create_dir(&self.db)?;
+		create_dir(&self.cache)?;
+		create_dir(&self.keys)?;
 		if signer_enabled {
-			fs::create_dir_all(&self.signer).map_err(|e| e.to_string())?;
+			create_dir(&self.signer)?;
 		}
 		if secretstore_enabled {
-			fs::create_dir_all(&self.secretstore).map_err(|e| e.to_string())?;
+			create_dir(&self.secretstore)?;
 		}
 		Ok(())
 	}
@@ -238,6 +243,14 @@ pub fn default_hypervisor_path() -> PathBuf {
 	get_app_root(AppDataType::UserData, &app_info).unwrap_or_else(|_| "$HOME/.parity-hypervisor".into())
 }
 
+/// Create a directory and its parents, reporting the path on failure.
+fn create_dir(path: &str) -> Result<(), String> {
+	fs::create_dir_all(path).map_err(|e| match e.kind() {
+		io::ErrorKind::PermissionDenied => format!("{}: cannot create directory {}", PERMISSION_DENIED_ERROR, path),
+		_ => format!("Cannot create directory {}: {}", path, e),
+	})
+}
+
 /// Get home directory.
 fn home() -> PathBuf {
 	home_dir().expect("Failed to get home dir")
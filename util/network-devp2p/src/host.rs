@@ -389,6 +389,44 @@ impl Host {
 		}
 	}
 
+	pub fn set_peer_limits(&self, min_peers: u32, max_peers: u32, io: &IoContext<NetworkIoMessage>) {
+		{
+			let mut info = self.info.write();
+			info.config.min_peers = min_peers;
+			info.config.max_peers = max_peers;
+		}
+
+		// disconnect the non-reserved peers which no longer fit in the new limits.
+		let min_peers = min_peers as usize;
+		let max_ingress = max(max(max_peers as usize, min_peers) - min_peers, min_peers / 2);
+		let reserved: HashSet<NodeId> = self.reserved_nodes.read().clone();
+		let mut egress_count = 0;
+		let mut ingress_count = 0;
+		let mut to_kill = Vec::new();
+		for e in self.sessions.read().iter() {
+			let mut s = e.lock();
+			if !s.is_ready() || s.id().map_or(false, |id| reserved.contains(id)) {
+				continue;
+			}
+
+			let over_limit = if s.info.originated {
+				egress_count += 1;
+				egress_count > min_peers
+			} else {
+				ingress_count += 1;
+				ingress_count > max_ingress
+			};
+			if over_limit {
+				s.disconnect(io, DisconnectReason::TooManyPeers);
+				to_kill.push(s.token());
+			}
+		}
+		for p in to_kill {
+			trace!(target: "network", "Disconnecting on peer limits change: {}", p);
+			self.kill_connection(p, io, false);
+		}
+	}
+
 	pub fn remove_reserved_node(&self, id: &str) -> Result<(), Error> {
 		let n = Node::from_str(id)?;
 		self.reserved_nodes.write().remove(&n.id);
@@ -406,6 +444,14 @@ impl Host {
 		format!("{}", Node::new(*info.id(), info.local_endpoint.clone()))
 	}
 
+	pub fn local_endpoint(&self) -> NodeEndpoint {
+		self.info.read().local_endpoint.clone()
+	}
+
+	pub fn node_id(&self) -> NodeId {
+		*self.info.read().id()
+	}
+
 	pub fn stop(&self, io: &IoContext<NetworkIoMessage>) {
 		self.stopping.store(true, AtomicOrdering::Release);
 		let mut to_kill = Vec::new();
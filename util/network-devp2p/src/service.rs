@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use network::{Error, NetworkConfiguration, NetworkProtocolHandler, NonReservedPeerMode};
+use network::{Error, NetworkConfiguration, NetworkProtocolHandler, NodeId, NonReservedPeerMode};
 use network::{NetworkContext, PeerId, ProtocolId, NetworkIoMessage};
 use host::Host;
 use io::*;
@@ -48,7 +48,7 @@ pub struct NetworkService {
 	host_info: String,
 	host: RwLock<Option<Arc<Host>>>,
 	host_handler: Arc<HostHandler>,
-	config: NetworkConfiguration,
+	config: RwLock<NetworkConfiguration>,
 	filter: Option<Arc<ConnectionFilter>>,
 }
 
@@ -62,7 +62,7 @@ impl NetworkService {
 			io_service,
 			host_info: config.client_version.clone(),
 			host: RwLock::new(None),
-			config,
+			config: RwLock::new(config),
 			host_handler,
 			filter,
 		})
@@ -98,8 +98,9 @@ impl NetworkService {
 	///
 	/// Keep in mind that `range.end` is *exclusive*.
 	pub fn num_peers_range(&self) -> Range<u32> {
-		let start = self.config.min_peers;
-		let end = self.config.max_peers + 1;
+		let config = self.config.read();
+		let start = config.min_peers;
+		let end = config.max_peers + 1;
 		start .. end
 	}
 
@@ -115,14 +116,31 @@ impl NetworkService {
 		host.as_ref().map(|h| h.local_url())
 	}
 
+	/// Returns the TCP port the host accepts connections on and the UDP port of the discovery, if
+	/// the host is running.
+	pub fn listen_ports(&self) -> Option<(u16, u16)> {
+		let host = self.host.read();
+		host.as_ref().map(|h| {
+			let endpoint = h.local_endpoint();
+			(endpoint.address.port(), endpoint.udp_port)
+		})
+	}
+
+	/// Returns the public key identifying the node on the network, if the host is running.
+	pub fn node_id(&self) -> Option<NodeId> {
+		let host = self.host.read();
+		host.as_ref().map(|h| h.node_id())
+	}
+
 	/// Start network IO.
 	///
 	/// In case of error, also returns the listening address for better error reporting.
 	pub fn start(&self) -> Result<(), (Error, Option<SocketAddr>)> {
 		let mut host = self.host.write();
-		let listen_addr = self.config.listen_address;
+		let config = self.config.read().clone();
+		let listen_addr = config.listen_address;
 		if host.is_none() {
-			let h = Arc::new(Host::new(self.config.clone(), self.filter.clone())
+			let h = Arc::new(Host::new(config, self.filter.clone())
 				.map_err(|err| (err, listen_addr))?);
 			self.io_service.register_handler(h.clone())
 				.map_err(|err| (err.into(), listen_addr))?;
@@ -181,6 +199,23 @@ impl NetworkService {
 		}
 	}
 
+	/// Set the number of peers the node connects to and the total number of peers allowed.
+	/// Applies to the running host, disconnecting the peers above the new limits, and to any
+	/// host started later.
+	pub fn set_peer_limits(&self, min_peers: u32, max_peers: u32) {
+		{
+			let mut config = self.config.write();
+			config.min_peers = min_peers;
+			config.max_peers = max_peers;
+		}
+
+		let host = self.host.read();
+		if let Some(ref host) = *host {
+			let io_ctxt = IoContext::new(self.io_service.channel(), 0);
+			host.set_peer_limits(min_peers, max_peers, &io_ctxt);
+		}
+	}
+
 	/// Executes action in the network context
 	pub fn with_context<F>(&self, protocol: ProtocolId, action: F) where F: FnOnce(&NetworkContext) {
 		let io = IoContext::new(self.io_service.channel(), 0);
@@ -53,7 +53,9 @@ This is a bug. Please report it at:
     https://github.com/paritytech/parity-ethereum/issues/new
 ";
 
-fn gen_panic_msg(info: &PanicInfo) -> String {
+/// Generates the message passed to the closure of `set_with`, with the backtrace of the panic and
+/// the link to report it.
+pub fn gen_panic_msg(info: &PanicInfo) -> String {
 	let location = info.location();
 	let file = location.as_ref().map(|l| l.file()).unwrap_or("<unknown>");
 	let line = location.as_ref().map(|l| l.line()).unwrap_or(0);
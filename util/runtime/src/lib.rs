@@ -23,7 +23,7 @@ use std::{fmt, thread};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 use futures::{future, Future, IntoFuture};
-pub use tokio::timer::Delay;
+pub use tokio::timer::{Delay, Interval};
 pub use tokio::runtime::{Runtime as TokioRuntime, Builder as TokioRuntimeBuilder, TaskExecutor};
 
 /// Runtime for futures.